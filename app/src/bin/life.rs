@@ -0,0 +1,17 @@
+use std::process::ExitCode;
+
+use app::game_of_life::cli::{self, CliError};
+
+fn main() -> ExitCode {
+    match cli::parse_args(std::env::args().skip(1)).and_then(|options| cli::run(&options)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("life: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,4 +1,6 @@
 pub mod component;
 pub mod emulator;
+pub mod settings;
+pub mod worker;
 
 pub use component::debugger::Debugger;
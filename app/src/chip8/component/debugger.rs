@@ -24,6 +24,8 @@ use super::stack_viewer::StackViewer;
 use crate::chip8::emulator::cpu::Keypad;
 use crate::chip8::emulator::engine::Emulator;
 use crate::chip8::emulator::screen::Screen;
+use crate::chip8::settings::{LastRom, Settings, SETTINGS_VERSION};
+use crate::chip8::worker::{Command, EmulatorWorker, Event};
 
 #[component]
 pub fn Debugger() -> impl IntoView {
@@ -35,23 +37,17 @@ pub fn Debugger() -> impl IntoView {
     let (delay_timer, set_delay_timer) = signal(emulator.cpu().get_delay_timer());
     let (stack, set_stack) = signal(emulator.cpu().get_stack());
     let (sp, set_sp) = signal(emulator.cpu().get_sp());
-    let (memory, set_memory) = signal(emulator.cpu().get_memory().to_owned());
+    // Filled in by the first memory diff the worker reports.
+    let (memory, set_memory) = signal(vec![0; emulator.cpu().get_memory().len()]);
     let (instruction_count, set_instruction_count) = signal(emulator.instruction_counter());
 
-    let emulator = StoredValue::new_local(emulator);
-
-    let quirks = RwSignal::new(emulator.get_value().quirks);
-    Effect::new(move |_| {
-        let new_settings = quirks.get();
-        emulator.update_value(|emu| {
-            emu.quirks = new_settings;
-        });
-    });
-
+    let quirks = RwSignal::new(emulator.quirks);
+    let timing = RwSignal::new(emulator.timing);
     let keypad = RwSignal::new(Keypad::default());
     let beeper = StoredValue::new_local(None::<Beeper>);
     let canvas_ref = NodeRef::<Canvas>::new();
     let ctx_ref = StoredValue::new_local(None::<CanvasRenderingContext2d>);
+    let screen = StoredValue::new_local(emulator.screen().to_owned());
 
     let (rom_name, set_rom_name) = signal(None);
     let (fps, set_fps) = signal(60.0);
@@ -60,24 +56,73 @@ pub fn Debugger() -> impl IntoView {
     let on_color = RwSignal::new("#000000".to_string());
     let off_color = RwSignal::new("#FFFFFF".to_string());
     let debug_mode = RwSignal::new(false);
+    let last_rom = RwSignal::new(None::<LastRom>);
+    let (status, set_status) = signal(String::new());
+
+    let on_event = move |event: Event| match event {
+        Event::Frame(frame) => {
+            set_pc(frame.pc);
+            set_instruction_count(frame.instruction_counter);
+            if let Some(audio) = beeper.get_value() {
+                if frame.beep {
+                    set_beep(true);
+                    audio.play();
+                } else {
+                    set_beep(false);
+                    audio.pause();
+                }
+            }
+            screen.set_value(Screen::from_rows(&frame.screen));
+            ctx_ref.with_value(|ctx| {
+                if let Some(ctx) = ctx {
+                    screen.with_value(|screen| {
+                        draw_screen(
+                            ctx,
+                            screen,
+                            on_color.get_untracked(),
+                            off_color.get_untracked(),
+                        )
+                    });
+                }
+            });
+        }
+        Event::State(state) => {
+            set_registers(state.registers);
+            set_index(state.index);
+            set_delay_timer(state.delay_timer);
+            set_sound_timer(state.sound_timer);
+            set_stack(state.stack);
+            set_sp(state.sp);
+            set_memory.update(|memory| {
+                for (addr, value) in state.memory_diff {
+                    memory[addr as usize] = value;
+                }
+            });
+        }
+        Event::Fallback(reason) => set_status(reason),
+        Event::Ready => {}
+    };
 
-    let sync = move || {
-        emulator.with_value(|emulator| {
-            set_pc(emulator.cpu().get_pc());
-            set_instruction_count(emulator.instruction_counter());
-
-            if debug_mode.get() {
-                set_registers(emulator.cpu().get_registers().to_owned());
-                set_index(emulator.cpu().get_index());
-                set_delay_timer(emulator.cpu().get_delay_timer());
-                set_sound_timer(emulator.cpu().get_sound_timer());
-                set_stack(emulator.cpu().get_stack());
-                set_sp(emulator.cpu().get_sp());
-                set_memory(emulator.cpu().get_memory().to_owned());
+    // The emulator itself runs in a web worker, so it must be spawned in the browser
+    // before any of the effects below send it commands.
+    let worker = StoredValue::new_local(None::<EmulatorWorker>);
+    Effect::new(move |_| {
+        worker.set_value(Some(EmulatorWorker::spawn(on_event)));
+    });
+    let send = move |command: Command| {
+        worker.with_value(|worker| {
+            if let Some(worker) = worker {
+                worker.send(command);
             }
         });
     };
 
+    Effect::new(move |_| send(Command::Quirks(quirks.get())));
+    Effect::new(move |_| send(Command::Timing(timing.get())));
+    Effect::new(move |_| send(Command::Keypad(keypad.get().0)));
+    // The worker only reports registers and memory while the debugger is visible.
+    Effect::new(move |_| send(Command::Debug(debug_mode.get())));
+
     let Pausable {
         pause,
         resume,
@@ -87,31 +132,7 @@ pub fn Debugger() -> impl IntoView {
             let dt = Duration::from_secs_f64(args.delta / 1000.0);
             set_frame_time(dt);
             set_fps(1000.0 / dt.as_millis_f64());
-            emulator.update_value(|emulator| {
-                emulator.update(keypad.get(), dt);
-                if let Some(audio) = beeper.get_value() {
-                    if emulator.is_beep() {
-                        set_beep(true);
-                        audio.play();
-                    } else {
-                        set_beep(false);
-                        audio.pause();
-                    }
-                }
-                ctx_ref.with_value(|ctx| {
-                    if let Some(ctx) = ctx {
-                        draw_screen(ctx, emulator.screen(), on_color.get(), off_color.get());
-                    }
-                });
-            });
-            sync();
-        }
-    });
-
-    // When switching to debug mode while paused, ensure we sync once
-    Effect::new(move |_| {
-        if debug_mode.get() && !is_active.get() {
-            sync();
+            send(Command::Tick(dt));
         }
     });
 
@@ -135,8 +156,8 @@ pub fn Debugger() -> impl IntoView {
         let off = off_color.get();
         ctx_ref.with_value(|ctx| {
             if let Some(ctx) = ctx {
-                emulator.with_value(|emu| {
-                    draw_screen(ctx, emu.screen(), on.clone(), off.clone());
+                screen.with_value(|screen| {
+                    draw_screen(ctx, screen, on.clone(), off.clone());
                 });
             }
         });
@@ -144,6 +165,52 @@ pub fn Debugger() -> impl IntoView {
 
     let selected_rom_url = RwSignal::new(String::new());
 
+    let load_rom = move |name: String, bytes: Vec<u8>| {
+        set_rom_name(Some(name));
+        send(Command::LoadRom(bytes));
+    };
+
+    // Restore saved preferences once the debugger is mounted in the browser.
+    let settings_restored = StoredValue::new(false);
+    Effect::new(move |_| {
+        if let Some(settings) = Settings::load() {
+            on_color.set(settings.on_color);
+            off_color.set(settings.off_color);
+            quirks.set(settings.quirks);
+            timing.set(settings.timing);
+            debug_mode.set(settings.debug_mode);
+            match settings.last_rom.clone() {
+                Some(LastRom::Url { url }) => {
+                    selected_rom_url.set(url.clone());
+                    spawn_local(async move {
+                        if let Some(bytes) = fetch_rom(&url).await {
+                            load_rom(url, bytes);
+                        }
+                    });
+                }
+                Some(LastRom::Upload { name, bytes }) => load_rom(name, bytes),
+                None => {}
+            }
+            last_rom.set(settings.last_rom);
+        }
+        settings_restored.set_value(true);
+    });
+
+    Effect::new(move |_| {
+        let settings = Settings {
+            version: SETTINGS_VERSION,
+            on_color: on_color.get(),
+            off_color: off_color.get(),
+            quirks: quirks.get(),
+            timing: timing.get(),
+            debug_mode: debug_mode.get(),
+            last_rom: last_rom.get(),
+        };
+        if settings_restored.get_value() {
+            settings.save();
+        }
+    });
+
     let file_input = NodeRef::<Input>::new();
     let on_file_upload = move |ev: ev::Event| {
         let input = event_target::<web_sys::HtmlInputElement>(&ev);
@@ -154,11 +221,11 @@ pub fn Debugger() -> impl IntoView {
                 match read_as_bytes(&file).await {
                     Ok(bytes) => {
                         leptos::logging::log!("ROM loaded: {} bytes", bytes.len());
-                        set_rom_name(Some(file.name()));
-                        emulator.update_value(|emulator| {
-                            emulator.reset();
-                            emulator.update_rom(bytes);
-                        });
+                        last_rom.set(Some(LastRom::Upload {
+                            name: file.name(),
+                            bytes: bytes.clone(),
+                        }));
+                        load_rom(file.name(), bytes);
                     }
                     Err(e) => leptos::logging::error!("Error reading file: {:?}", e),
                 }
@@ -180,15 +247,9 @@ pub fn Debugger() -> impl IntoView {
         });
     };
 
-    let reset = move || {
-        emulator.update_value(|e| e.reset());
-        sync();
-    };
+    let reset = move || send(Command::Reset);
 
-    let step = move |steps: u32| {
-        emulator.update_value(|e| e.step(keypad.get(), steps));
-        sync();
-    };
+    let step = move |steps: u32| send(Command::Step(steps));
 
     let roms: Vec<(&'static str, &'static str)> = vec![
         ("IBM Logo", "/roms/IBMLogo.ch8"),
@@ -205,25 +266,10 @@ pub fn Debugger() -> impl IntoView {
             selected_rom_url.set(url.clone());
 
             spawn_local(async move {
-                match Request::get(&url).send().await {
-                    Ok(res) => {
-                        if !res.ok() {
-                            leptos::logging::error!("Failed to fetch ROM: Status {}", res.status());
-                            return;
-                        }
-                        match res.binary().await {
-                            Ok(bytes) => {
-                                set_rom_name(Some(url));
-                                emulator.update_value(|emulator| {
-                                    emulator.reset();
-                                    emulator.update_rom(bytes);
-                                });
-                                resume();
-                            }
-                            Err(e) => leptos::logging::error!("Failed to get bytes: {:?}", e),
-                        }
-                    }
-                    Err(e) => leptos::logging::error!("Failed to fetch ROM: {:?}", e),
+                if let Some(bytes) = fetch_rom(&url).await {
+                    last_rom.set(Some(LastRom::Url { url: url.clone() }));
+                    load_rom(url, bytes);
+                    resume();
                 }
             });
         }
@@ -290,11 +336,12 @@ pub fn Debugger() -> impl IntoView {
                     }
                 }
                 />
+                <div class="emulator-status">{move || status.get()}</div>
 
                 <Show when=move || debug_mode.get()>
                     <hr class="divider"/>
                     <div class="panel-header">"Quirks / Compatibility"</div>
-                    <QuirkSettings quirks />
+                    <QuirkSettings quirks timing />
                     <hr class="divider"/>
                     <div class="panel-header">"Display Colors"</div>
                     <ColorSettings on_color off_color />
@@ -311,6 +358,28 @@ pub fn Debugger() -> impl IntoView {
     }
 }
 
+async fn fetch_rom(url: &str) -> Option<Vec<u8>> {
+    match Request::get(url).send().await {
+        Ok(res) => {
+            if !res.ok() {
+                leptos::logging::error!("Failed to fetch ROM: Status {}", res.status());
+                return None;
+            }
+            match res.binary().await {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    leptos::logging::error!("Failed to get bytes: {:?}", e);
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            leptos::logging::error!("Failed to fetch ROM: {:?}", e);
+            None
+        }
+    }
+}
+
 fn draw_screen(
     ctx: &CanvasRenderingContext2d,
     screen: &Screen,
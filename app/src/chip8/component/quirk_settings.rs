@@ -1,8 +1,12 @@
 use crate::chip8::emulator::quirks::Quirks;
+use crate::chip8::emulator::timing::Timing;
 use leptos::prelude::*;
 
 #[component]
-pub fn QuirkSettings(#[prop(into)] quirks: RwSignal<Quirks>) -> impl IntoView {
+pub fn QuirkSettings(
+    #[prop(into)] quirks: RwSignal<Quirks>,
+    #[prop(into)] timing: RwSignal<Timing>,
+) -> impl IntoView {
     view! {
         <div class="quirks-panel">
             <div class="settings-list">
@@ -83,6 +87,19 @@ pub fn QuirkSettings(#[prop(into)] quirks: RwSignal<Quirks>) -> impl IntoView {
                     />
                     <label for="q_jump">"Jumping (Bnnn)"</label>
                 </div>
+
+                // 7. Timing
+                <div class="checkbox-row" title="On: Instructions take as long as on a COSMAC VIP. Off: Fixed instructions per frame.">
+                    <input
+                        type="checkbox" id="q_vip_timing"
+                        prop:checked=move || timing.get() == Timing::CosmacVip
+                        on:change=move |ev| {
+                            let c = event_target_checked(&ev);
+                            timing.set(if c { Timing::CosmacVip } else { Timing::Fixed });
+                        }
+                    />
+                    <label for="q_vip_timing">"VIP Timing"</label>
+                </div>
             </div>
         </div>
     }
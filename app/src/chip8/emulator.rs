@@ -1,4 +1,6 @@
 pub mod cpu;
 pub mod engine;
+pub mod observer;
 pub mod quirks;
 pub mod screen;
+pub mod timing;
@@ -1,4 +1,5 @@
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 use super::quirks::Quirks;
@@ -96,6 +97,12 @@ impl CPU {
         &self.screen
     }
 
+    /// True after a sprite draw with the `display_wait` quirk, until the next
+    /// call to `tick_timers`.
+    pub fn is_vblank_waiting(&self) -> bool {
+        self.vblank_waiting
+    }
+
     pub fn fetch(&self) -> u16 {
         let pc = self.pc as usize;
         (self.memory.0[pc] as u16) << 8 | self.memory.0[pc + 1] as u16
@@ -360,7 +367,7 @@ impl Memory {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Registers([u8; 16]);
 
 impl Registers {
@@ -626,4 +633,5 @@ impl Keypad {
 
 #[cfg(test)]
 #[path = "cpu_test.rs"]
+#[allow(clippy::bool_assert_comparison, clippy::redundant_pattern_matching)]
 mod cpu_test;
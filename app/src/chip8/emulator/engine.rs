@@ -1,18 +1,28 @@
 use std::time::Duration;
 
-use super::cpu::{Keypad, CPU};
+use super::cpu::{Instruction, Keypad, Register, CPU};
+use super::observer::{Chip8Observer, Observers};
 use super::quirks::Quirks;
 use super::screen::Screen;
+use super::timing::{self, Timing};
 
 #[derive(Debug, Default, Clone)]
 pub struct Emulator {
     pub quirks: Quirks,
+    pub timing: Timing,
     cpu: CPU,
     rom: Option<Vec<u8>>,
     vsync_screen: Screen,
-    cycle_accumulator: f32,
-    timer_accumulator: f32,
+    /// Wall time not yet spent on whole frames.
+    frame_accumulator: f32,
+    frame_counter: u64,
+    /// Instructions, or machine cycles under `Timing::CosmacVip`, already spent
+    /// in the current frame.
+    frame_progress: u32,
     instruction_counter: u64,
+    observers: Observers,
+    /// Whether observers were already told about the current `GetKey` wait.
+    key_waiting: bool,
 }
 
 impl Emulator {
@@ -26,10 +36,8 @@ impl Emulator {
     }
 
     pub fn reset(&mut self) {
-        self.instruction_counter = 0;
         self.cpu = CPU::new(None);
-        self.cycle_accumulator = 0.0;
-        self.timer_accumulator = 0.0;
+        self.reset_counters();
     }
 
     pub fn update_rom(&mut self, rom: Vec<u8>) {
@@ -38,10 +46,26 @@ impl Emulator {
     }
 
     pub fn reload_rom(&mut self) {
-        self.instruction_counter = 0;
         self.cpu = CPU::new(self.rom.as_ref());
-        self.cycle_accumulator = 0.0;
-        self.timer_accumulator = 0.0;
+        self.reset_counters();
+    }
+
+    fn reset_counters(&mut self) {
+        self.instruction_counter = 0;
+        self.frame_counter = 0;
+        self.frame_progress = 0;
+        self.frame_accumulator = 0.0;
+        self.vsync_screen = Screen::default();
+        self.key_waiting = false;
+    }
+
+    /// Observers are kept across resets and ROM changes.
+    pub fn add_observer(&mut self, observer: impl Chip8Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
     }
 
     pub fn cpu(&self) -> &CPU {
@@ -62,38 +86,151 @@ impl Emulator {
 
     pub const TARGET_IPS: u32 = 700;
 
+    /// Rate of the vertical blank, which also drives the delay and sound timers.
+    pub const FRAME_RATE: u32 = 60;
+
     pub fn instruction_counter(&self) -> u64 {
         self.instruction_counter
     }
 
-    /// Emulate a given amount of time passing.
+    pub fn frame_counter(&self) -> u64 {
+        self.frame_counter
+    }
+
+    /// Work that fits in the current frame, measured like `frame_progress`.
+    /// `TARGET_IPS` doesn't divide evenly into frames, so the remainder is spread
+    /// out such that every second of frames executes exactly `TARGET_IPS`
+    /// instructions.
+    fn frame_budget(&self) -> u32 {
+        match self.timing {
+            Timing::Fixed => {
+                let ips = Self::TARGET_IPS as u64;
+                let fps = Self::FRAME_RATE as u64;
+                let frame = self.frame_counter % fps;
+                ((frame + 1) * ips / fps - frame * ips / fps) as u32
+            }
+            Timing::CosmacVip => timing::VIP_FRAME_BUDGET,
+        }
+    }
+
+    /// Cost of the next instruction, measured like `frame_progress`.
+    fn instruction_cost(&self, keypad: Keypad, instruction: Option<&Instruction>) -> u32 {
+        match (self.timing, instruction) {
+            (Timing::CosmacVip, Some(instruction)) => {
+                timing::vip_cycles(&self.cpu, instruction, keypad, &self.quirks)
+            }
+            _ => 1,
+        }
+    }
+
+    /// Emulate a given amount of time passing, one whole frame at a time.
     pub fn update(&mut self, keypad: Keypad, dt: Duration) {
-        let dt = dt.as_secs_f32();
-        self.cycle_accumulator += dt;
-        let cycle_duration = 1.0 / Self::TARGET_IPS as f32;
-
-        let cycles = (self.cycle_accumulator / cycle_duration) as u32;
-        if cycles > 0 {
-            self.step(keypad, cycles);
-            self.cycle_accumulator -= cycles as f32 * cycle_duration;
+        self.frame_accumulator += dt.as_secs_f32();
+        let frame_duration = 1.0 / Self::FRAME_RATE as f32;
+        while self.frame_accumulator >= frame_duration {
+            self.run_frame(keypad);
+            self.frame_accumulator -= frame_duration;
         }
     }
 
-    /// Emulate a given number of instructions.
-    pub fn step(&mut self, keypad: Keypad, instructions: u32) {
-        let cycle_duration = 1.0 / Self::TARGET_IPS as f32;
-        let timer_step = 1.0 / 60.0;
+    /// Emulate the rest of the current frame: execute what is left of its
+    /// instruction budget, then tick the timers exactly once.
+    ///
+    /// With the `display_wait` quirk a sprite draw idles the CPU until the next
+    /// vertical blank, as on the COSMAC VIP and in Octo, so at most one sprite is
+    /// drawn per frame while the timers keep pace with wall time.
+    pub fn run_frame(&mut self, keypad: Keypad) {
+        while self.frame_progress < self.frame_budget() && !self.cpu.is_vblank_waiting() {
+            self.execute(keypad);
+        }
+        self.end_frame();
+    }
 
+    /// Emulate a given number of instructions, ending frames as their budget
+    /// is used up.
+    pub fn step(&mut self, keypad: Keypad, instructions: u32) {
         for _ in 0..instructions {
-            self.cpu.tick(keypad, &self.quirks);
-            self.instruction_counter += 1;
-            self.timer_accumulator += cycle_duration;
-
-            while self.timer_accumulator >= timer_step {
-                self.vsync_screen = self.cpu.get_screen().to_owned();
-                self.cpu.tick_timers();
-                self.timer_accumulator -= timer_step;
+            self.execute(keypad);
+            if self.frame_progress >= self.frame_budget() || self.cpu.is_vblank_waiting() {
+                self.end_frame();
+            }
+        }
+    }
+
+    fn execute(&mut self, keypad: Keypad) {
+        let instruction = Instruction::decode(self.cpu.fetch());
+        self.frame_progress += self.instruction_cost(keypad, instruction.as_ref());
+        match instruction {
+            Some(instruction) if !self.observers.is_empty() => {
+                self.tick_observed(keypad, &instruction)
+            }
+            _ => self.cpu.tick(keypad, &self.quirks),
+        }
+        self.instruction_counter += 1;
+    }
+
+    /// `CPU::tick`, reporting what the instruction did to the observers.
+    fn tick_observed(&mut self, keypad: Keypad, instruction: &Instruction) {
+        let pc = self.cpu.get_pc();
+        let index = self.cpu.get_index();
+        let beep = self.cpu.is_beep();
+        // VF may be the sprite's X or Y register, and is overwritten by the draw.
+        let sprite = match *instruction {
+            Instruction::Display(x, y, height) => {
+                Some((self.cpu.get_register(x), self.cpu.get_register(y), height))
+            }
+            _ => None,
+        };
+        for observer in self.observers.iter_mut() {
+            observer.before_instruction(&self.cpu, instruction);
+        }
+
+        self.cpu.tick(keypad, &self.quirks);
+
+        let written = match *instruction {
+            Instruction::BinaryDecimalConversion(_) => 3,
+            Instruction::StoreMemory(x) => x as u16 + 1,
+            _ => 0,
+        };
+        let key_wait = match *instruction {
+            Instruction::GetKey(vx) if self.cpu.get_pc() == pc => Some(vx),
+            _ => None,
+        };
+        let cpu = &self.cpu;
+        for observer in self.observers.iter_mut() {
+            for addr in index..index + written {
+                observer.memory_write(addr, cpu.get_memory()[addr as usize]);
+            }
+            if let Some((x, y, height)) = sprite {
+                observer.draw(x, y, height, cpu.get_register(Register::VF) != 0);
+            }
+            match (beep, cpu.is_beep()) {
+                (false, true) => observer.sound_start(),
+                (true, false) => observer.sound_stop(),
+                _ => {}
+            }
+            if let (Some(vx), false) = (key_wait, self.key_waiting) {
+                observer.key_wait(vx);
+            }
+            observer.after_instruction(cpu, instruction);
+        }
+        self.key_waiting = key_wait.is_some();
+    }
+
+    /// The vertical blank: latch the screen and tick the timers. An instruction
+    /// interrupted by the vertical blank finishes afterwards, so any work past the
+    /// budget is carried into the next frame.
+    fn end_frame(&mut self) {
+        let budget = self.frame_budget();
+        let beep = self.cpu.is_beep();
+        self.vsync_screen = self.cpu.get_screen().to_owned();
+        self.cpu.tick_timers();
+        if beep && !self.cpu.is_beep() {
+            for observer in self.observers.iter_mut() {
+                observer.sound_stop();
             }
         }
+        self.frame_counter += 1;
+        self.frame_progress = self.frame_progress.saturating_sub(budget);
     }
 }
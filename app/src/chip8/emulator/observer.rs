@@ -0,0 +1,64 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use super::cpu::{Instruction, Register, CPU};
+
+/// Instrumentation hooks called by `Emulator` as it executes. Every method
+/// defaults to doing nothing, so an observer only implements what it needs.
+///
+/// Observers can't change emulation; share state with them (e.g. through an
+/// `Rc<RefCell<_>>`) to read back what they collected.
+pub trait Chip8Observer {
+    /// Called with the CPU state before `instruction` executes.
+    fn before_instruction(&mut self, _cpu: &CPU, _instruction: &Instruction) {}
+
+    /// Called with the CPU state after `instruction` executed.
+    fn after_instruction(&mut self, _cpu: &CPU, _instruction: &Instruction) {}
+
+    /// Called for every byte an instruction stored to memory.
+    fn memory_write(&mut self, _addr: u16, _value: u8) {}
+
+    /// Called after a sprite is drawn at (`x`, `y`), with whether any pixel was
+    /// turned off.
+    fn draw(&mut self, _x: u8, _y: u8, _height: u8, _collision: bool) {}
+
+    /// Called when the sound timer becomes non-zero.
+    fn sound_start(&mut self) {}
+
+    /// Called when the sound timer runs out or is cleared.
+    fn sound_stop(&mut self) {}
+
+    /// Called when the CPU starts waiting for a key to store in `register`.
+    fn key_wait(&mut self, _register: Register) {}
+}
+
+/// The observers attached to an `Emulator`. Observers can't be cloned, so a
+/// cloned emulator starts without any.
+#[derive(Default)]
+pub struct Observers(Vec<Box<dyn Chip8Observer>>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Observers::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+impl Deref for Observers {
+    type Target = Vec<Box<dyn Chip8Observer>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Observers {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+use serde::{Deserialize, Serialize};
+
+/// Quirks left out when deserializing are taken from `Quirks::MODERN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Quirks {
     /// If true, logic ops reset VF.
     pub vf_reset: bool,
@@ -25,3 +29,9 @@ impl Quirks {
         jumping: true,
     };
 }
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::MODERN
+    }
+}
@@ -6,3 +6,26 @@ impl Default for Screen {
         Self([[false; 64]; 32])
     }
 }
+
+impl Screen {
+    /// Packs each row into a bitmask with the leftmost pixel in the highest bit.
+    pub fn to_rows(&self) -> [u64; 32] {
+        let mut rows = [0; 32];
+        for (packed, row) in rows.iter_mut().zip(self.0.iter()) {
+            for (x, &pixel) in row.iter().enumerate() {
+                *packed |= (pixel as u64) << (63 - x);
+            }
+        }
+        rows
+    }
+
+    pub fn from_rows(rows: &[u64; 32]) -> Self {
+        let mut screen = Self::default();
+        for (row, packed) in screen.0.iter_mut().zip(rows.iter()) {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = (packed >> (63 - x)) & 1 == 1;
+            }
+        }
+        screen
+    }
+}
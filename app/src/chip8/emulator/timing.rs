@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use super::cpu::{Cond, Instruction, Keypad, Register, CPU};
+use super::quirks::Quirks;
+
+/// How the emulator decides how much work fits in a 60 Hz frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Timing {
+    /// Every instruction costs the same, at `Emulator::TARGET_IPS`.
+    #[default]
+    Fixed,
+    /// Instructions cost as many machine cycles as they take in the COSMAC VIP
+    /// interpreter, and each frame has the cycles left over between display
+    /// interrupts.
+    CosmacVip,
+}
+
+/// Machine cycles between two vertical blank interrupts. The VIP's 1802 runs at
+/// 1.7609 MHz with 8 clocks per machine cycle.
+pub const VIP_CYCLES_PER_FRAME: u32 = 3668;
+/// Cycles per frame taken by the CDP1861's DMA to display 128 scanlines of 8 bytes.
+pub const VIP_DISPLAY_DMA_CYCLES: u32 = 1024;
+/// Cycles per frame spent in the interrupt routine, which also ticks the timers.
+pub const VIP_INTERRUPT_CYCLES: u32 = 29;
+
+/// Cycles per frame left over for the interpreter.
+pub const VIP_FRAME_BUDGET: u32 =
+    VIP_CYCLES_PER_FRAME - VIP_DISPLAY_DMA_CYCLES - VIP_INTERRUPT_CYCLES;
+
+/// The interpreter's fetch and dispatch loop, paid by every instruction.
+const FETCH_CYCLES: u32 = 40;
+
+const DRAW_SETUP: u32 = 68;
+/// Loading, XORing and collision checking one byte aligned sprite row.
+const DRAW_ROW: u32 = 46;
+/// Each bit a sprite row is shifted right to line up with X.
+const DRAW_ROW_SHIFT: u32 = 8;
+/// Rows that aren't byte aligned touch a second byte of display memory.
+const DRAW_ROW_SPLIT: u32 = 26;
+
+/// Approximate machine cycles the VIP interpreter takes to execute
+/// `instruction` in the current CPU state, following published disassemblies
+/// of the original interpreter.
+pub fn vip_cycles(cpu: &CPU, instruction: &Instruction, keypad: Keypad, quirks: &Quirks) -> u32 {
+    FETCH_CYCLES + execute_cycles(cpu, instruction, keypad, quirks)
+}
+
+fn execute_cycles(cpu: &CPU, instruction: &Instruction, keypad: Keypad, quirks: &Quirks) -> u32 {
+    use Instruction::*;
+    match instruction {
+        DisplayClear => 24,
+        Return | Call(_) | Jump(_) | CallSubroutine(_) | JumpOffset(..) => 23,
+        CondSkip(cond) => {
+            let (compare, skipped) = match *cond {
+                Cond::Eq(vx, nn) => (12, cpu.get_register(vx) == nn),
+                Cond::Neq(vx, nn) => (12, cpu.get_register(vx) != nn),
+                Cond::EqReg(vx, vy) => (16, cpu.get_register(vx) == cpu.get_register(vy)),
+                Cond::NeqReg(vx, vy) => (16, cpu.get_register(vx) != cpu.get_register(vy)),
+            };
+            compare + if skipped { 2 } else { 0 }
+        }
+        SetRegister(..) => 6,
+        Add(..) | GetDelay(_) | SetDelay(_) | SetSound(_) => 10,
+        Assign(..) | Or(..) | And(..) | Xor(..) | AddReg(..) | Subtract(..) | SubtractOther(..)
+        | ShiftLeft(..) | ShiftRight(..) => 44,
+        SetIndex(_) => 12,
+        Rand(..) => 36,
+        Display(x, y, height) => draw_cycles(
+            cpu.get_register(*x),
+            cpu.get_register(*y),
+            *height,
+            quirks.clipping,
+        ),
+        SkipIfKey(vx) | SkipIfNotKey(vx) => {
+            let pressed = keypad.is_pressed(cpu.get_register(*vx) & 0xF);
+            let skipped = pressed == matches!(instruction, SkipIfKey(_));
+            16 + if skipped { 2 } else { 0 }
+        }
+        // One pass through the keyboard polling loop.
+        GetKey(_) => 12,
+        AddIndex(_) => 19,
+        FontCharacter(_) => 20,
+        BinaryDecimalConversion(vx) => 84 + 12 * digit_sum(cpu.get_register(*vx)),
+        StoreMemory(x) | LoadMemory(x) => 14 + 14 * (register_count(*x)),
+    }
+}
+
+/// DXYN time grows with the number of rows drawn and with how far X is from a
+/// byte boundary, since the VIP shifts each row into place one bit at a time.
+fn draw_cycles(x: u8, y: u8, height: u8, clipping: bool) -> u32 {
+    let y = (y % 32) as u32;
+    let rows = if clipping {
+        (height as u32).min(32 - y)
+    } else {
+        height as u32
+    };
+    let shift = (x % 8) as u32;
+    let row = DRAW_ROW + shift * DRAW_ROW_SHIFT + if shift > 0 { DRAW_ROW_SPLIT } else { 0 };
+    DRAW_SETUP + rows * row
+}
+
+/// FX33 converts to decimal by repeated subtraction, one loop per unit counted.
+fn digit_sum(value: u8) -> u32 {
+    (value / 100 + (value / 10) % 10 + value % 10) as u32
+}
+
+fn register_count(x: Register) -> u32 {
+    x as u32 + 1
+}
+
+#[cfg(test)]
+#[path = "timing_test.rs"]
+mod timing_test;
@@ -0,0 +1,66 @@
+use super::*;
+use crate::chip8::emulator::engine::Emulator;
+
+fn cycles(cpu: &CPU, instruction: Instruction) -> u32 {
+    vip_cycles(cpu, &instruction, Keypad::default(), &Quirks::MODERN)
+}
+
+#[test]
+fn test_vip_frame_runs_a_known_mix() {
+    // ROM:
+    // 0x6005 - LD V0, 5     (46 cycles, once)
+    // 0x7001 - ADD V0, 1    (50 cycles)
+    // 0x8010 - LD V0, V1    (84 cycles)
+    // 0xA300 - LD I, 0x300  (52 cycles)
+    // 0x1202 - JP 0x202     (63 cycles)
+    let rom = vec![0x60, 0x05, 0x70, 0x01, 0x80, 0x10, 0xA3, 0x00, 0x12, 0x02];
+    let mut emulator = Emulator::new(Some(rom));
+    emulator.timing = Timing::CosmacVip;
+
+    // 46 + 10 loops of 249 leave 79 of the 2615 cycles, enough to start an ADD
+    // and an LD V0, V1, which runs 55 cycles into the next frame.
+    emulator.run_frame(Keypad::default());
+    assert_eq!(emulator.instruction_counter(), 43);
+    // 55 + 52 + 63 + 9 loops leave 204 cycles, for one more whole loop that
+    // runs 45 cycles over.
+    emulator.run_frame(Keypad::default());
+    assert_eq!(emulator.instruction_counter(), 85);
+    assert_eq!(emulator.frame_counter(), 2);
+}
+
+#[test]
+fn test_draw_cycles_grow_with_height() {
+    assert!(draw_cycles(0, 0, 15, true) > draw_cycles(0, 0, 1, true));
+    assert_eq!(draw_cycles(0, 0, 0, true), DRAW_SETUP);
+}
+
+#[test]
+fn test_draw_cycles_unaligned_is_slower() {
+    let aligned = draw_cycles(8, 0, 5, true);
+    let unaligned = draw_cycles(9, 0, 5, true);
+    let more_unaligned = draw_cycles(15, 0, 5, true);
+    assert!(aligned < unaligned);
+    assert!(unaligned < more_unaligned);
+}
+
+#[test]
+fn test_draw_cycles_clipped_rows_are_free() {
+    assert_eq!(draw_cycles(0, 30, 15, true), draw_cycles(0, 0, 2, true));
+    assert_eq!(draw_cycles(0, 30, 15, false), draw_cycles(0, 0, 15, false));
+}
+
+#[test]
+fn test_skip_costs_more_when_taken() {
+    let cpu = CPU::new(None);
+    let taken = cycles(&cpu, Instruction::CondSkip(Cond::Eq(Register::V0, 0)));
+    let not_taken = cycles(&cpu, Instruction::CondSkip(Cond::Eq(Register::V0, 1)));
+    assert_eq!(taken, not_taken + 2);
+}
+
+#[test]
+fn test_memory_ops_scale_with_register_count() {
+    let cpu = CPU::new(None);
+    let one = cycles(&cpu, Instruction::StoreMemory(Register::V0));
+    let all = cycles(&cpu, Instruction::StoreMemory(Register::VF));
+    assert_eq!(all - one, 15 * 14);
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::emulator::quirks::Quirks;
+use super::emulator::timing::Timing;
+
+const STORAGE_KEY: &str = "chip8.settings";
+
+/// Bump this whenever a field is renamed or changes meaning, and append the
+/// matching upgrade step to `MIGRATIONS`. Purely additive fields don't need a
+/// new version since missing fields fall back to their defaults.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0];
+
+/// User preferences that survive a page reload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    pub on_color: String,
+    pub off_color: String,
+    pub quirks: Quirks,
+    pub timing: Timing,
+    pub debug_mode: bool,
+    pub last_rom: Option<LastRom>,
+}
+
+/// The most recently loaded ROM, either one of the bundled ROMs or a file the
+/// user uploaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LastRom {
+    Url { url: String },
+    Upload { name: String, bytes: Vec<u8> },
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            on_color: "#000000".to_string(),
+            off_color: "#FFFFFF".to_string(),
+            quirks: Quirks::MODERN,
+            timing: Timing::Fixed,
+            debug_mode: false,
+            last_rom: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Parses a stored settings document, upgrading it from older versions.
+    /// Documents written by a newer version are rejected rather than guessed at.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+        if version > SETTINGS_VERSION as usize {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported settings version {version}"
+            )));
+        }
+        for migration in &MIGRATIONS[version..] {
+            migration(&mut value);
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_string(), SETTINGS_VERSION.into());
+        }
+        serde_json::from_value(value)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("settings are always serializable")
+    }
+
+    /// Reads the saved settings from local storage, if any.
+    pub fn load() -> Option<Self> {
+        let json = storage()?.get_item(STORAGE_KEY).ok()??;
+        match Self::from_json(&json) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                log::warn!("Discarding saved chip8 settings: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        if let Some(storage) = storage() {
+            if storage.set_item(STORAGE_KEY, &self.to_json()).is_err() {
+                log::warn!("Failed to save chip8 settings");
+            }
+        }
+    }
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Documents without a version predate versioning and have nothing to rename.
+fn migrate_v0(_value: &mut Value) {}
+
+#[cfg(test)]
+#[path = "settings_test.rs"]
+mod settings_test;
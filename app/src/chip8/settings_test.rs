@@ -0,0 +1,61 @@
+use super::*;
+
+#[test]
+fn test_settings_round_trip() {
+    let settings = Settings {
+        on_color: "#00FF00".to_string(),
+        debug_mode: true,
+        last_rom: Some(LastRom::Upload {
+            name: "test.ch8".to_string(),
+            bytes: vec![0x12, 0x00],
+        }),
+        ..Default::default()
+    };
+    assert_eq!(Settings::from_json(&settings.to_json()).unwrap(), settings);
+}
+
+#[test]
+fn test_settings_missing_fields_use_defaults() {
+    let settings = Settings::from_json(r##"{"version": 1, "off_color": "#123456"}"##).unwrap();
+    assert_eq!(settings.off_color, "#123456");
+    assert_eq!(settings.on_color, Settings::default().on_color);
+    assert_eq!(settings.quirks, Quirks::MODERN);
+    assert_eq!(settings.last_rom, None);
+}
+
+#[test]
+fn test_settings_partial_quirks_default_to_modern() {
+    let settings = Settings::from_json(r#"{"quirks": {"vf_reset": false}}"#).unwrap();
+    assert_eq!(
+        settings.quirks,
+        Quirks {
+            vf_reset: false,
+            ..Quirks::MODERN
+        }
+    );
+}
+
+#[test]
+fn test_settings_unversioned_document_is_migrated() {
+    let settings = Settings::from_json(r#"{"debug_mode": true}"#).unwrap();
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert!(settings.debug_mode);
+}
+
+#[test]
+fn test_settings_future_version_is_rejected() {
+    let json = format!(r#"{{"version": {}}}"#, SETTINGS_VERSION + 1);
+    assert!(Settings::from_json(&json).is_err());
+}
+
+#[test]
+fn test_settings_last_rom_url() {
+    let settings =
+        Settings::from_json(r#"{"last_rom": {"kind": "url", "url": "/roms/Pong.ch8"}}"#).unwrap();
+    assert_eq!(
+        settings.last_rom,
+        Some(LastRom::Url {
+            url: "/roms/Pong.ch8".to_string()
+        })
+    );
+}
@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker, WorkerOptions, WorkerType};
+
+use super::emulator::cpu::{Keypad, Registers};
+use super::emulator::engine::Emulator;
+use super::emulator::quirks::Quirks;
+use super::emulator::timing::Timing;
+
+/// Bootstraps the wasm bundle inside the worker and calls `run_worker`.
+const WORKER_SCRIPT: &str = "/chip8-worker.js";
+
+/// Messages sent from the UI to the emulator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    LoadRom(Vec<u8>),
+    Reset,
+    /// Emulate `dt` of wall time, normally sent once per animation frame.
+    Tick(Duration),
+    /// Execute exactly this many instructions.
+    Step(u32),
+    Keypad(u16),
+    Quirks(Quirks),
+    Timing(Timing),
+    /// Toggle whether a `State` accompanies every `Frame`.
+    Debug(bool),
+}
+
+/// Messages sent from the emulator back to the UI.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    /// The worker has installed its message handler.
+    Ready,
+    Frame(Frame),
+    State(CpuState),
+    /// The emulator moved to the main thread, for the reason given.
+    Fallback(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// See `Screen::to_rows`.
+    pub screen: [u64; 32],
+    pub beep: bool,
+    pub pc: u16,
+    pub instruction_counter: u64,
+}
+
+/// Debugger view of the CPU. Memory is sent as the bytes that changed since
+/// the previous `State` so the UI never has to receive the full 4 KiB again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuState {
+    pub registers: Registers,
+    pub index: u16,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub stack: [u16; 16],
+    pub sp: usize,
+    pub memory_diff: Vec<(u16, u8)>,
+}
+
+/// Emulator side of the protocol, independent of how messages are delivered.
+pub struct WorkerCore {
+    emulator: Emulator,
+    keypad: Keypad,
+    debug: bool,
+    /// Memory as last reported to the UI, which starts out zeroed.
+    reported_memory: Vec<u8>,
+}
+
+impl Default for WorkerCore {
+    fn default() -> Self {
+        let emulator = Emulator::new(None);
+        let reported_memory = vec![0; emulator.cpu().get_memory().len()];
+        Self {
+            emulator,
+            keypad: Keypad::default(),
+            debug: false,
+            reported_memory,
+        }
+    }
+}
+
+impl WorkerCore {
+    pub fn handle(&mut self, command: Command) -> Vec<Event> {
+        match command {
+            Command::LoadRom(rom) => {
+                self.emulator.reset();
+                self.emulator.update_rom(rom);
+            }
+            Command::Reset => self.emulator.reset(),
+            Command::Tick(dt) => self.emulator.update(self.keypad, dt),
+            Command::Step(instructions) => self.emulator.step(self.keypad, instructions),
+            Command::Keypad(keys) => {
+                self.keypad = Keypad(keys);
+                return Vec::new();
+            }
+            Command::Quirks(quirks) => {
+                self.emulator.quirks = quirks;
+                return Vec::new();
+            }
+            Command::Timing(timing) => {
+                self.emulator.timing = timing;
+                return Vec::new();
+            }
+            Command::Debug(debug) => {
+                self.debug = debug;
+                return if debug {
+                    vec![Event::State(self.state())]
+                } else {
+                    Vec::new()
+                };
+            }
+        }
+        let mut events = vec![Event::Frame(self.frame())];
+        if self.debug {
+            events.push(Event::State(self.state()));
+        }
+        events
+    }
+
+    fn frame(&self) -> Frame {
+        Frame {
+            screen: self.emulator.screen().to_rows(),
+            beep: self.emulator.is_beep(),
+            pc: self.emulator.cpu().get_pc(),
+            instruction_counter: self.emulator.instruction_counter(),
+        }
+    }
+
+    fn state(&mut self) -> CpuState {
+        let cpu = self.emulator.cpu();
+        let memory_diff = cpu
+            .get_memory()
+            .iter()
+            .zip(self.reported_memory.iter_mut())
+            .enumerate()
+            .filter(|(_, (new, old))| new != old)
+            .map(|(addr, (&new, old))| {
+                *old = new;
+                (addr as u16, new)
+            })
+            .collect();
+        CpuState {
+            registers: cpu.get_registers().to_owned(),
+            index: cpu.get_index(),
+            delay_timer: cpu.get_delay_timer(),
+            sound_timer: cpu.get_sound_timer(),
+            stack: cpu.get_stack(),
+            sp: cpu.get_sp(),
+            memory_diff,
+        }
+    }
+}
+
+/// Worker entry point: services `Command`s posted by `EmulatorWorker`.
+pub fn run_worker() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let core = RefCell::new(WorkerCore::default());
+
+    let post = {
+        let scope = scope.clone();
+        move |event: &Event| {
+            let json = serde_json::to_string(event).expect("events are always serializable");
+            if let Err(e) = scope.post_message(&JsValue::from_str(&json)) {
+                log::error!("Failed to post chip8 event: {e:?}");
+            }
+        }
+    };
+
+    let on_message = {
+        let post = post.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |message: MessageEvent| {
+            let Some(json) = message.data().as_string() else {
+                return;
+            };
+            match serde_json::from_str(&json) {
+                Ok(command) => core.borrow_mut().handle(command).iter().for_each(&post),
+                Err(e) => log::error!("Invalid chip8 command: {e}"),
+            }
+        })
+    };
+    scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // The handler lives for as long as the worker does.
+    on_message.forget();
+    post(&Event::Ready);
+}
+
+/// How long the worker has to report `Ready` before the emulator runs on the
+/// main thread instead.
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// UI side handle to the emulator. Runs the emulator in a dedicated worker,
+/// falling back to running it on the main thread if the worker can't start,
+/// fails, or never reports `Ready`.
+pub struct EmulatorWorker {
+    backend: Rc<RefCell<Backend>>,
+    on_event: Rc<dyn Fn(Event)>,
+    /// The worker's handlers, kept outside `backend` so falling back from
+    /// inside one doesn't drop it.
+    _handlers: Option<WorkerHandlers>,
+}
+
+struct WorkerHandlers {
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(JsValue)>,
+}
+
+enum Backend {
+    Worker {
+        worker: Worker,
+        /// Commands sent before the worker reported `Ready`, other than ticks.
+        pending: Option<Vec<Command>>,
+        /// Enough of the commands sent to set a new emulator up the same way.
+        setup: Vec<Command>,
+    },
+    Local(Box<WorkerCore>),
+}
+
+impl EmulatorWorker {
+    pub fn spawn(on_event: impl Fn(Event) + 'static) -> Self {
+        let on_event: Rc<dyn Fn(Event)> = Rc::new(on_event);
+        let options = WorkerOptions::new();
+        options.set_type(WorkerType::Module);
+        let worker = match Worker::new_with_options(WORKER_SCRIPT, &options) {
+            Ok(worker) => worker,
+            Err(e) => {
+                log::warn!("Running chip8 on the main thread, worker failed to start: {e:?}");
+                return Self {
+                    backend: Rc::new(RefCell::new(Backend::Local(Box::default()))),
+                    on_event,
+                    _handlers: None,
+                };
+            }
+        };
+
+        let backend = Rc::new(RefCell::new(Backend::Worker {
+            worker: worker.clone(),
+            pending: Some(Vec::new()),
+            setup: Vec::new(),
+        }));
+        let on_message = {
+            let backend = Rc::downgrade(&backend);
+            let on_event = on_event.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |message: MessageEvent| {
+                let Some(json) = message.data().as_string() else {
+                    return;
+                };
+                match serde_json::from_str(&json) {
+                    Ok(Event::Ready) => {
+                        if let Some(backend) = backend.upgrade() {
+                            flush(&backend);
+                        }
+                    }
+                    Ok(event) => on_event(event),
+                    Err(e) => log::error!("Invalid chip8 event: {e}"),
+                }
+            })
+        };
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        let on_error = {
+            let backend = Rc::downgrade(&backend);
+            let on_event = on_event.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |error: JsValue| {
+                log::error!("chip8 worker failed: {error:?}");
+                if let Some(backend) = backend.upgrade() {
+                    fall_back(&backend, on_event.as_ref(), "the worker failed");
+                }
+            })
+        };
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        {
+            let backend = Rc::downgrade(&backend);
+            let on_event = on_event.clone();
+            leptos::prelude::set_timeout(
+                move || {
+                    let Some(backend) = backend.upgrade() else {
+                        return;
+                    };
+                    let waiting = matches!(
+                        &*backend.borrow(),
+                        Backend::Worker {
+                            pending: Some(_),
+                            ..
+                        }
+                    );
+                    if waiting {
+                        fall_back(&backend, on_event.as_ref(), "the worker never got ready");
+                    }
+                },
+                READY_TIMEOUT,
+            );
+        }
+
+        Self {
+            backend,
+            on_event,
+            _handlers: Some(WorkerHandlers {
+                _on_message: on_message,
+                _on_error: on_error,
+            }),
+        }
+    }
+
+    pub fn send(&self, command: Command) {
+        let events = match &mut *self.backend.borrow_mut() {
+            Backend::Worker {
+                worker,
+                pending,
+                setup,
+            } => {
+                remember(setup, &command);
+                match pending {
+                    // Time spent waiting for the worker isn't caught up on.
+                    Some(_) if matches!(command, Command::Tick(_)) => {}
+                    Some(pending) => pending.push(command),
+                    None => post_command(worker, &command),
+                }
+                return;
+            }
+            Backend::Local(core) => core.handle(command),
+        };
+        events.into_iter().for_each(|event| (self.on_event)(event));
+    }
+}
+
+impl Drop for EmulatorWorker {
+    fn drop(&mut self) {
+        if let Backend::Worker { worker, .. } = &*self.backend.borrow() {
+            worker.terminate();
+        }
+    }
+}
+
+/// Sends the commands held back until the worker was ready.
+fn flush(backend: &RefCell<Backend>) {
+    if let Backend::Worker {
+        worker, pending, ..
+    } = &mut *backend.borrow_mut()
+    {
+        for command in pending.take().unwrap_or_default() {
+            post_command(worker, &command);
+        }
+    }
+}
+
+/// Stops the worker and runs the emulator on the main thread instead. A worker
+/// that never got ready has the commands it never got replayed, and one that
+/// failed while running is replaced by an emulator set up the same way, which
+/// starts the program again.
+fn fall_back(backend: &RefCell<Backend>, on_event: &dyn Fn(Event), reason: &str) {
+    let events: Vec<Event> = {
+        let mut backend = backend.borrow_mut();
+        let Backend::Worker {
+            worker,
+            pending,
+            setup,
+        } = &mut *backend
+        else {
+            return;
+        };
+        log::warn!("Running chip8 on the main thread, {reason}");
+        worker.terminate();
+        let commands = pending.take().unwrap_or_else(|| std::mem::take(setup));
+        let mut core = Box::<WorkerCore>::default();
+        let mut events: Vec<Event> = commands
+            .into_iter()
+            .flat_map(|command| core.handle(command))
+            .collect();
+        events.push(Event::Fallback(format!(
+            "Running on the main thread, {reason}"
+        )));
+        *backend = Backend::Local(core);
+        events
+    };
+    events.into_iter().for_each(on_event);
+}
+
+/// Adds `command` to the commands that set an emulator up, replacing the
+/// last one of its kind. Ticks and steps only run the program, so are left
+/// out.
+fn remember(setup: &mut Vec<Command>, command: &Command) {
+    if matches!(command, Command::Tick(_) | Command::Step(_)) {
+        return;
+    }
+    let kind = std::mem::discriminant(command);
+    setup.retain(|earlier| std::mem::discriminant(earlier) != kind);
+    setup.push(command.clone());
+}
+
+fn post_command(worker: &Worker, command: &Command) {
+    let json = serde_json::to_string(command).expect("commands are always serializable");
+    if let Err(e) = worker.post_message(&JsValue::from_str(&json)) {
+        log::error!("Failed to post chip8 command: {e:?}");
+    }
+}
+
+#[cfg(test)]
+#[path = "worker_test.rs"]
+mod worker_test;
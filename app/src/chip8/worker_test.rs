@@ -0,0 +1,107 @@
+use super::*;
+use crate::chip8::emulator::screen::Screen;
+
+fn frames(events: &[Event]) -> Vec<&Frame> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Frame(frame) => Some(frame),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_command_round_trip() {
+    for command in [
+        Command::LoadRom(vec![0x12, 0x00]),
+        Command::Tick(Duration::from_millis(16)),
+        Command::Quirks(Quirks::MODERN),
+        Command::Timing(Timing::CosmacVip),
+        Command::Keypad(0x8001),
+    ] {
+        let json = serde_json::to_string(&command).unwrap();
+        assert_eq!(serde_json::from_str::<Command>(&json).unwrap(), command);
+    }
+}
+
+#[test]
+fn test_screen_rows_round_trip() {
+    let mut screen = Screen::default();
+    screen.0[0][0] = true;
+    screen.0[5][63] = true;
+    screen.0[31][17] = true;
+    let rows = screen.to_rows();
+    assert_eq!(rows[0], 1 << 63);
+    assert_eq!(rows[5], 1);
+    assert_eq!(Screen::from_rows(&rows).0, screen.0);
+}
+
+#[test]
+fn test_step_reports_frame_without_state() {
+    let mut core = WorkerCore::default();
+    core.handle(Command::LoadRom(vec![0x60, 0x0A, 0x12, 0x02]));
+    let events = core.handle(Command::Step(2));
+    assert_eq!(events.len(), 1);
+    assert_eq!(frames(&events)[0].instruction_counter, 2);
+    assert_eq!(frames(&events)[0].pc, 0x202);
+}
+
+#[test]
+fn test_input_commands_are_silent() {
+    let mut core = WorkerCore::default();
+    assert!(core.handle(Command::Keypad(0x1)).is_empty());
+    assert!(core.handle(Command::Quirks(Quirks::default())).is_empty());
+    assert!(core.handle(Command::Timing(Timing::CosmacVip)).is_empty());
+    assert!(core.handle(Command::Debug(false)).is_empty());
+}
+
+#[test]
+fn test_debug_state_sends_memory_diffs() {
+    let mut core = WorkerCore::default();
+    core.handle(Command::LoadRom(vec![0x60, 0x0A, 0xA3, 0x00, 0xF0, 0x55]));
+
+    let Some(Event::State(state)) = core.handle(Command::Debug(true)).pop() else {
+        panic!("enabling debug should report state");
+    };
+    // Font and ROM bytes, skipping the zeroes.
+    assert!(state.memory_diff.contains(&(0x200, 0x60)));
+    assert!(state.memory_diff.contains(&(0x50, 0xF0)));
+    assert!(!state.memory_diff.iter().any(|&(_, value)| value == 0));
+
+    let Some(Event::State(state)) = core.handle(Command::Step(3)).pop() else {
+        panic!("debug mode should report state after stepping");
+    };
+    assert_eq!(state.memory_diff, vec![(0x300, 0x0A)]);
+    assert_eq!(state.index, 0x301);
+}
+
+#[test]
+fn test_setup_keeps_the_latest_of_each_command() {
+    let mut setup = Vec::new();
+    for command in [
+        Command::LoadRom(vec![0x12, 0x00]),
+        Command::Tick(Duration::from_millis(16)),
+        Command::Quirks(Quirks::MODERN),
+        Command::Reset,
+        Command::Step(1),
+        Command::LoadRom(vec![0x00, 0xE0]),
+        Command::Quirks(Quirks {
+            display_wait: true,
+            ..Quirks::MODERN
+        }),
+    ] {
+        remember(&mut setup, &command);
+    }
+    assert_eq!(
+        setup,
+        vec![
+            Command::Reset,
+            Command::LoadRom(vec![0x00, 0xE0]),
+            Command::Quirks(Quirks {
+                display_wait: true,
+                ..Quirks::MODERN
+            }),
+        ]
+    );
+}
@@ -6,7 +6,7 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub red: u8,
     pub green: u8,
@@ -31,3 +31,15 @@ pub fn hex_color(input: &str) -> IResult<&str, Color> {
 
     Ok((input, Color { red, green, blue }))
 }
+
+/// A `#RRGGBB` colour as RGB bytes.
+pub fn rgb(hex: &str) -> [u8; 3] {
+    let (_, color) = hex_color(hex).expect("palettes hold hex colours");
+    [color.red, color.green, color.blue]
+}
+
+/// `from` moved `amount` of the way to `to`.
+pub fn mix(from: [u8; 3], to: [u8; 3], amount: f64) -> [u8; 3] {
+    let amount = amount.clamp(0.0, 1.0);
+    [0, 1, 2].map(|c| (from[c] as f64 + (to[c] as f64 - from[c] as f64) * amount).round() as u8)
+}
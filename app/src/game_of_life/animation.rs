@@ -0,0 +1,175 @@
+use leptos::prelude::*;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use wasm_bindgen::JsCast;
+
+use super::engine::encoding::{AnimationError, AnimationFormat, AnimationOptions};
+use crate::color::{hex_color, Color};
+
+/// Most generations one recording runs for.
+const MAX_GENERATIONS: u32 = 1000;
+/// Largest number of pixels each cell is drawn as.
+const MAX_SCALE: u32 = 16;
+/// How long the browser has to start saving a download before its object URL
+/// is revoked.
+const REVOKE_DELAY: Duration = Duration::from_secs(30);
+
+/// Has the browser save `bytes` as a file called `name`.
+fn download(bytes: &[u8], mime_type: &str, name: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let link = document()
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    // Revoking straight away can cancel the download in some browsers.
+    set_timeout(
+        move || {
+            if let Err(e) = web_sys::Url::revoke_object_url(&url) {
+                log::warn!("Unable to revoke download URL: {e:?}");
+            }
+        },
+        REVOKE_DELAY,
+    );
+    Ok(())
+}
+
+/// Records the selection, or the whole universe, for a number of
+/// generations and downloads it as an animated GIF or APNG.
+#[component]
+pub fn AnimationExport(
+    #[prop(into)] record: Callback<AnimationOptions, Result<Vec<u8>, AnimationError>>,
+) -> impl IntoView {
+    let options = RwSignal::new(AnimationOptions::default());
+    let (status, set_status) = signal(String::new());
+
+    let set_number = move |value: String, max: u32, set: fn(&mut AnimationOptions, u32)| {
+        if let Ok(number) = value.parse::<u32>() {
+            options.update(|options| set(options, number.clamp(1, max)));
+        }
+    };
+    let set_color = move |value: String, set: fn(&mut AnimationOptions, Color)| {
+        if let Ok((_, color)) = hex_color(&value) {
+            options.update(|options| set(options, color));
+        }
+    };
+    let to_hex = |color: Color| format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue);
+
+    let on_record = move |_| {
+        let options = options.get_untracked();
+        set_status(String::from("Recording..."));
+        // Gives the status a chance to show before the page is busy recording.
+        set_timeout(
+            move || {
+                let saved = record
+                    .run(options)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| {
+                        let name = format!("life.{}", options.format.extension());
+                        download(&bytes, options.format.mime_type(), &name)
+                            .map(|_| bytes.len())
+                            .map_err(|e| format!("{e:?}"))
+                    });
+                set_status(match saved {
+                    Ok(size) => format!(
+                        "Saved {} generations as {} ({} KB), running the universe on to match",
+                        options.generations,
+                        options.format,
+                        size.div_ceil(1024)
+                    ),
+                    Err(e) => format!("Unable to record: {e}"),
+                });
+            },
+            Duration::ZERO,
+        );
+    };
+
+    view! {
+        <div class="life-animation">
+            <select
+                class="animation-format"
+                prop:value=move || options.get().format.to_string()
+                on:change=move |ev| {
+                    if let Ok(format) = event_target_value(&ev).parse::<AnimationFormat>() {
+                        options.update(|options| options.format = format);
+                    }
+                }
+            >
+                {AnimationFormat::iter()
+                    .map(|format| view! { <option value=format.to_string()>{format.to_string()}</option> })
+                    .collect_view()}
+            </select>
+            <label class="animation-number">
+                "Generations: "
+                <input
+                    type="number"
+                    min="1"
+                    max=MAX_GENERATIONS
+                    prop:value=move || options.get().generations
+                    on:change=move |ev| set_number(
+                        event_target_value(&ev),
+                        MAX_GENERATIONS,
+                        |options, generations| options.generations = generations,
+                    )
+                />
+            </label>
+            <label class="animation-number">
+                "Scale: "
+                <input
+                    type="number"
+                    min="1"
+                    max=MAX_SCALE
+                    prop:value=move || options.get().scale
+                    on:change=move |ev| set_number(
+                        event_target_value(&ev),
+                        MAX_SCALE,
+                        |options, scale| options.scale = scale,
+                    )
+                />
+            </label>
+            <label class="animation-number">
+                "Frame (ms): "
+                <input
+                    type="number"
+                    min="1"
+                    max=u16::MAX
+                    prop:value=move || options.get().delay
+                    on:change=move |ev| set_number(
+                        event_target_value(&ev),
+                        u32::from(u16::MAX),
+                        |options, delay| options.delay = delay as u16,
+                    )
+                />
+            </label>
+            <label class="animation-color">
+                "Alive "
+                <input
+                    type="color"
+                    prop:value=move || to_hex(options.get().alive)
+                    on:input=move |ev| set_color(event_target_value(&ev), |options, color| options.alive = color)
+                />
+            </label>
+            <label class="animation-color">
+                "Dead "
+                <input
+                    type="color"
+                    prop:value=move || to_hex(options.get().dead)
+                    on:input=move |ev| set_color(event_target_value(&ev), |options, color| options.dead = color)
+                />
+            </label>
+            <button
+                class="game-button"
+                title="Runs the universe on by the generations recorded, which can't be undone"
+                on:click=on_record
+            >
+                "Record"
+            </button>
+            <span class="animation-status">{move || status.get()}</span>
+        </div>
+    }
+}
@@ -1,16 +1,52 @@
+// Cell coordinates run into the thousands of pixels, beyond what mediump
+// can address a pixel at a time.
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
 precision mediump float;
+#endif
 
-uniform float vpw; // Width, in pixels
 uniform float vph; // Height, in pixels
 
-uniform vec2 pitch; // idk like the cell size or something
+uniform vec2 offset; // The texture cell at the canvas' top left corner
+uniform float zoom; // Pixels per cell
+uniform vec2 cells; // Size of the texture, in cells
+uniform bool grid; // Whether to draw lines between the cells
+uniform vec3 gridColor;
 
 uniform sampler2D uSampler; // give me cells
 
+// For GPU universes, uSampler only holds the preview, and the cells come from
+// the universe's own texture: states in red, repeating across the plane.
+uniform bool indexed;
+uniform sampler2D uStates;
+uniform vec2 origin; // The universe cell at the top left of uSampler
+uniform vec2 universeSize;
+uniform sampler2D palette; // A colour for each state
+uniform float states;
+
+// Selected cells as (first column, first row, last column, last row) within
+// the texture, or empty when nothing is selected.
+uniform vec4 selection;
+
 void main() {
-	if (int(mod(gl_FragCoord.x, pitch[0])) == 0 || int(mod(gl_FragCoord.y, pitch[1])) == 0) {
-		gl_FragColor = vec4(0.0, 0.0, 0.0, 0.5);
+	// Row 0 of the texture is at the top of the canvas.
+	vec2 position = vec2(gl_FragCoord.x, vph - gl_FragCoord.y);
+	vec2 cell = offset + position / zoom;
+	if (grid && any(lessThan(fract(cell) * zoom, vec2(1.0)))) {
+		gl_FragColor = vec4(gridColor, 1.0);
+	} else if (indexed) {
+		vec2 wrapped = mod(floor(cell) + origin, universeSize);
+		float state = floor(texture2D(uStates, (wrapped + 0.5) / universeSize).r * 255.0 + 0.5);
+		gl_FragColor = texture2D(palette, vec2((state + 0.5) / states, 0.5));
+		vec4 ghost = texture2D(uSampler, cell / cells);
+		gl_FragColor = mix(gl_FragColor, vec4(ghost.rgb, 1.0), ghost.a * 0.5);
 	} else {
-		gl_FragColor = texture2D(uSampler, gl_FragCoord.xy / vec2(vpw, vph));
+		gl_FragColor = texture2D(uSampler, cell / cells);
+	}
+
+	cell = floor(cell);
+	if (all(greaterThanEqual(cell, selection.xy)) && all(lessThanEqual(cell, selection.zw))) {
+		gl_FragColor = mix(gl_FragColor, vec4(0.2, 0.4, 1.0, 1.0), 0.35);
 	}
 }
@@ -0,0 +1,294 @@
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use image::ImageFormat;
+
+use super::engine::encoding::{self, AnimationError, AnimationOptions};
+use super::engine::pattern::{Pattern, PatternError, MAX_CELLS};
+use super::engine::rule::Rule;
+use super::engine::soup::Soup;
+use super::engine::universe::{Backend, Selection, Topology, UniverseBackend};
+
+pub const USAGE: &str = "\
+Usage: life [OPTIONS] <INPUT>
+
+Runs the RLE or .cells pattern in INPUT, or standard input if it is -, and
+writes the result.
+
+Options:
+  -g, --generations <N>  Generations to run, up to 2^40 with hashlife and 2^20
+                         on a grid [default: 0]
+  -o, --output <FILE>    Where to write, picking the format from the extension
+                         [default: standard output]
+  -f, --format <FORMAT>  rle, png or text [default: text]
+  -b, --backend <NAME>   hashlife, torus, dead-border, klein-bottle or growing
+                         [default: hashlife, or growing for rules it can't run]
+  -s, --size <WxH>       Grid size for the grid backends [default: the pattern
+                         with room around it]
+      --scale <N>        Pixels a side for each cell of a PNG [default: 4]
+  -h, --help             Print this help";
+
+/// Cells left on each side of a pattern on a grid, so it has some room to
+/// move before it reaches the edges.
+const MARGIN: u32 = 16;
+/// Most generations run. Hashlife keeps positions in `i64`s, and patterns run
+/// much longer can spread past them.
+const MAX_GENERATIONS: u64 = 1 << 40;
+/// Most generations run on a grid, which ticks them one at a time.
+const MAX_GRID_GENERATIONS: u64 = 1 << 20;
+
+/// What the result is written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// A `.cells` grid, a row to a line.
+    #[default]
+    Text,
+    Rle,
+    Png,
+}
+
+impl FromStr for Format {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Format, CliError> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "txt" | "cells" => Ok(Format::Text),
+            "rle" => Ok(Format::Rle),
+            "png" => Ok(Format::Png),
+            _ => Err(CliError::Usage(format!("unknown format {s:?}"))),
+        }
+    }
+}
+
+impl Format {
+    /// The format a file's extension asks for.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("{USAGE}")]
+    Help,
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("{0} can't run {1}")]
+    Unsupported(Backend, Rule),
+    #[error("{0} can't run more than {1} generations")]
+    TooLong(Backend, u64),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Pattern(#[from] PatternError),
+    #[error("a {0}x{1} result is more than the {MAX_CELLS} cells that can be written")]
+    TooLarge(u64, u64),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Animation(#[from] AnimationError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The pattern to run, with `-` for standard input.
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub format: Format,
+    pub generations: u64,
+    /// The backend to run on, or `None` to pick one that suits the rule.
+    pub backend: Option<Backend>,
+    pub size: Option<(u32, u32)>,
+    pub scale: u32,
+}
+
+/// Reads the options from the command line arguments, not counting the
+/// program's name.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+    let mut args = args.into_iter();
+    let mut input = None;
+    let mut output: Option<PathBuf> = None;
+    let mut format = None;
+    let mut generations = 0;
+    let mut backend = None;
+    let mut size = None;
+    let mut scale = AnimationOptions::default().scale;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| CliError::Usage(format!("{arg} needs a value")))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "-g" | "--generations" => generations = parse_number(&value()?)?,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => format = Some(value()?.parse()?),
+            "-b" | "--backend" => backend = Some(parse_backend(&value()?)?),
+            "-s" | "--size" => size = Some(parse_size(&value()?)?),
+            "--scale" => scale = parse_number(&value()?)?,
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(CliError::Usage(format!("unknown option {flag}")))
+            }
+            _ if input.is_some() => {
+                return Err(CliError::Usage(format!("unexpected argument {arg}")))
+            }
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+
+    if generations > MAX_GENERATIONS {
+        return Err(CliError::Usage(format!(
+            "can't run more than {MAX_GENERATIONS} generations"
+        )));
+    }
+    if scale == 0 {
+        return Err(CliError::Usage(String::from("scale must be at least 1")));
+    }
+    let format = format
+        .or_else(|| output.as_deref().and_then(Format::from_path))
+        .unwrap_or_default();
+    Ok(Options {
+        input: input.ok_or_else(|| CliError::Usage(String::from("no input pattern")))?,
+        output,
+        format,
+        generations,
+        backend,
+        size,
+        scale,
+    })
+}
+
+fn parse_number<T: FromStr>(value: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("{value:?} isn't a number")))
+}
+
+/// A backend from its name, in any case and with dashes for spaces. The GPU
+/// backend needs a browser, so isn't one of them.
+fn parse_backend(name: &str) -> Result<Backend, CliError> {
+    Backend::all()
+        .filter(|&backend| backend != Backend::Gpu)
+        .find(|backend| {
+            backend
+                .to_string()
+                .replace(' ', "-")
+                .eq_ignore_ascii_case(name)
+        })
+        .ok_or_else(|| CliError::Usage(format!("unknown backend {name:?}")))
+}
+
+fn parse_size(size: &str) -> Result<(u32, u32), CliError> {
+    let (width, height) = size
+        .split_once(['x', 'X'])
+        .ok_or_else(|| CliError::Usage(format!("size {size:?} isn't WxH")))?;
+    let (width, height) = (parse_number(width)?, parse_number(height)?);
+    if width == 0 || height == 0 {
+        return Err(CliError::Usage(format!("size {size:?} is empty")));
+    }
+    Ok((width, height))
+}
+
+/// Hashlife for rules it can run, otherwise a grid that grows to fit.
+pub fn default_backend(rule: Rule) -> Backend {
+    if Backend::HashLife.supports(rule) {
+        Backend::HashLife
+    } else {
+        Backend::Grid(Topology::Infinite)
+    }
+}
+
+/// A universe of `backend` holding `pattern` after `generations`
+/// generations. Grids are `size`, or big enough for the pattern and a margin
+/// around it, with the pattern in the middle.
+pub fn simulate(
+    pattern: &Pattern,
+    backend: Backend,
+    size: Option<(u32, u32)>,
+    generations: u64,
+) -> Result<Box<dyn UniverseBackend>, CliError> {
+    let rule = pattern.rule.unwrap_or_default();
+    if !backend.supports(rule) {
+        return Err(CliError::Unsupported(backend, rule));
+    }
+    let limit = match backend {
+        Backend::HashLife => MAX_GENERATIONS,
+        _ => MAX_GRID_GENERATIONS,
+    };
+    if generations > limit {
+        return Err(CliError::TooLong(backend, limit));
+    }
+    let (width, height) = size.unwrap_or((pattern.width + 2 * MARGIN, pattern.height + 2 * MARGIN));
+    // An empty soup.
+    let mut universe = backend.create(width, height, rule, Soup::new(0, 0.0));
+    universe.insert_pattern(
+        pattern,
+        (height.saturating_sub(pattern.height) / 2) as i64,
+        (width.saturating_sub(pattern.width) / 2) as i64,
+    );
+    universe.run(generations);
+    Ok(universe)
+}
+
+/// The live cells of `universe`, trimmed to the smallest rectangle holding
+/// them, as `format`.
+pub fn write_universe(
+    universe: &dyn UniverseBackend,
+    format: Format,
+    scale: u32,
+) -> Result<Vec<u8>, CliError> {
+    // A single dead cell stands in for an empty universe.
+    let area = universe.bounds().unwrap_or(Selection {
+        width: 1,
+        height: 1,
+        ..Selection::default()
+    });
+    let (width, height) = (u64::from(area.width), u64::from(area.height));
+    if format != Format::Png && width * height > MAX_CELLS {
+        return Err(CliError::TooLarge(width, height));
+    }
+    Ok(match format {
+        Format::Text => universe.to_pattern(area).to_plaintext().into_bytes(),
+        Format::Rle => universe.to_pattern(area).to_rle().into_bytes(),
+        Format::Png => {
+            encoding::check_size(area, scale)?;
+            let options = AnimationOptions {
+                scale,
+                ..AnimationOptions::default()
+            };
+            let mut png = Cursor::new(Vec::new());
+            encoding::frame(universe, area, &options).write_to(&mut png, ImageFormat::Png)?;
+            png.into_inner()
+        }
+    })
+}
+
+/// Loads the input pattern, runs it and writes the result where `options`
+/// say.
+pub fn run(options: &Options) -> Result<(), CliError> {
+    let source = if options.input == Path::new("-") {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        source
+    } else {
+        fs::read_to_string(&options.input)?
+    };
+    let pattern = Pattern::parse(&source)?;
+    let backend = options
+        .backend
+        .unwrap_or_else(|| default_backend(pattern.rule.unwrap_or_default()));
+    let universe = simulate(&pattern, backend, options.size, options.generations)?;
+    let bytes = write_universe(universe.as_ref(), options.format, options.scale)?;
+    match &options.output {
+        Some(path) => fs::write(path, bytes)?,
+        None => io::stdout().write_all(&bytes)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "cli_test.rs"]
+mod cli_test;
@@ -0,0 +1,156 @@
+use super::*;
+use crate::game_of_life::engine::library;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn test_parse_args() {
+    let options = parse_args(args("-g 100 --backend dead-border -s 40x30 glider.rle")).unwrap();
+    assert_eq!(
+        options,
+        Options {
+            input: PathBuf::from("glider.rle"),
+            output: None,
+            format: Format::Text,
+            generations: 100,
+            backend: Some(Backend::Grid(Topology::Bounded)),
+            size: Some((40, 30)),
+            scale: AnimationOptions::default().scale,
+        }
+    );
+}
+
+#[test]
+fn test_format_from_output() {
+    let options = parse_args(args("-o out.png -")).unwrap();
+    assert_eq!(options.format, Format::Png);
+    assert_eq!(options.input, PathBuf::from("-"));
+    let options = parse_args(args("-o out.png --format rle -")).unwrap();
+    assert_eq!(options.format, Format::Rle);
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert!(matches!(parse_args(args("-h")), Err(CliError::Help)));
+    assert!(matches!(parse_args(args("")), Err(CliError::Usage(_))));
+    assert!(matches!(parse_args(args("a b")), Err(CliError::Usage(_))));
+    assert!(matches!(parse_args(args("-g")), Err(CliError::Usage(_))));
+    assert!(matches!(
+        parse_args(args("-g x a")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(args("-s 0x4 a")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(args("-b gpu a")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(args("--fast a")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(args("--scale 0 a")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(args("-g 4611686018427387904 a")),
+        Err(CliError::Usage(_))
+    ));
+}
+
+#[test]
+fn test_backends_agree() {
+    // The R-pentomino is still growing after 200 generations, so any
+    // difference between the engines shows up.
+    let pattern = library::load("R-pentomino").unwrap();
+    let expected = write_universe(
+        simulate(&pattern, Backend::HashLife, None, 200)
+            .unwrap()
+            .as_ref(),
+        Format::Rle,
+        1,
+    )
+    .unwrap();
+    let grid = simulate(&pattern, Backend::Grid(Topology::Infinite), None, 200).unwrap();
+    assert_eq!(
+        write_universe(grid.as_ref(), Format::Rle, 1).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_glider_moves() {
+    let glider = library::load("Glider").unwrap();
+    let universe = simulate(&glider, Backend::Grid(Topology::Torus), None, 8).unwrap();
+    let text = write_universe(universe.as_ref(), Format::Text, 1).unwrap();
+    assert_eq!(String::from_utf8(text).unwrap(), ".O.\n..O\nOOO\n");
+    // Two cells down and right of where it was centred.
+    let area = universe.bounds().unwrap();
+    let start = MARGIN as i64 + 2;
+    assert_eq!((area.row, area.column), (start, start));
+}
+
+#[test]
+fn test_empty_universe() {
+    let blank = Pattern::new(2, 2);
+    let universe = simulate(&blank, Backend::HashLife, None, 10).unwrap();
+    assert_eq!(universe.bounds(), None);
+    let text = write_universe(universe.as_ref(), Format::Text, 1).unwrap();
+    assert_eq!(text, b".\n");
+}
+
+#[test]
+fn test_unsupported_rule() {
+    let mut pattern = library::load("Glider").unwrap();
+    pattern.rule = Some("B2/S/C3".parse().unwrap());
+    assert!(matches!(
+        simulate(&pattern, Backend::HashLife, None, 1),
+        Err(CliError::Unsupported(..))
+    ));
+    assert_eq!(
+        default_backend(pattern.rule.unwrap()),
+        Backend::Grid(Topology::Infinite)
+    );
+}
+
+#[test]
+fn test_grids_run_fewer_generations() {
+    let glider = library::load("Glider").unwrap();
+    let torus = Backend::Grid(Topology::Torus);
+    assert!(matches!(
+        simulate(&glider, torus, None, MAX_GRID_GENERATIONS + 1),
+        Err(CliError::TooLong(_, MAX_GRID_GENERATIONS))
+    ));
+    assert!(simulate(&glider, Backend::HashLife, None, MAX_GRID_GENERATIONS + 1).is_ok());
+}
+
+#[test]
+fn test_write_png() {
+    let glider = library::load("Glider").unwrap();
+    let universe = simulate(&glider, Backend::HashLife, None, 0).unwrap();
+    let png = write_universe(universe.as_ref(), Format::Png, 2).unwrap();
+    let image = image::load_from_memory(&png).unwrap();
+    assert_eq!((image.width(), image.height()), (6, 6));
+}
+
+#[test]
+fn test_write_too_large() {
+    let glider = library::load("Glider").unwrap();
+    let universe = simulate(&glider, Backend::HashLife, None, 0).unwrap();
+    assert!(matches!(
+        write_universe(universe.as_ref(), Format::Png, 4_000_000_000),
+        Err(CliError::Animation(_))
+    ));
+    let mut universe = Backend::HashLife.create(1, 1, Rule::CONWAY, Soup::new(0, 0.0));
+    universe.set_state(0, 0, Rule::ALIVE);
+    universe.set_state(9999, 9999, Rule::ALIVE);
+    assert!(matches!(
+        write_universe(universe.as_ref(), Format::Rle, 1),
+        Err(CliError::TooLarge(10000, 10000))
+    ));
+}
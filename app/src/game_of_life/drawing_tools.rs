@@ -0,0 +1,99 @@
+use leptos::ev;
+use leptos::leptos_dom::helpers::window_event_listener;
+use leptos::prelude::*;
+use strum::IntoEnumIterator;
+use wasm_bindgen::JsCast;
+
+use super::tools::Tool;
+
+/// Editing commands, from the toolbar or the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Undo,
+    Redo,
+    Copy,
+    Cut,
+    Paste,
+    /// Kills the selected cells.
+    Delete,
+    RotateSelection,
+    FlipSelectionHorizontally,
+    FlipSelectionVertically,
+}
+
+/// Tool palette with undo, redo and the clipboard. The selection commands
+/// only apply while cells are selected.
+#[component]
+pub fn DrawingTools(
+    #[prop(into)] tool: RwSignal<Tool>,
+    #[prop(into)] has_selection: Signal<bool>,
+    #[prop(into)] can_paste: Signal<bool>,
+    #[prop(into)] action: Callback<Action>,
+) -> impl IntoView {
+    Effect::new(move |_| {
+        let handle_keydown = window_event_listener(ev::keydown, move |ev| {
+            // Leave typing in the rule and pattern boxes alone.
+            let typing = ev
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+            if typing {
+                return;
+            }
+            let command = ev.ctrl_key() || ev.meta_key();
+            let picked = match ev.code().as_str() {
+                "KeyZ" if command && ev.shift_key() => Action::Redo,
+                "KeyZ" if command => Action::Undo,
+                "KeyY" if command => Action::Redo,
+                "KeyC" if command && has_selection.get_untracked() => Action::Copy,
+                "KeyX" if command && has_selection.get_untracked() => Action::Cut,
+                "KeyV" if command && can_paste.get_untracked() => Action::Paste,
+                "Delete" | "Backspace" if has_selection.get_untracked() => Action::Delete,
+                _ => return,
+            };
+            ev.prevent_default();
+            action.run(picked);
+        });
+        on_cleanup(move || handle_keydown.remove());
+    });
+
+    let button = move |label: &'static str, picked: Action, enabled: Signal<bool>| {
+        view! {
+            <button
+                class="tool-button"
+                disabled=move || !enabled.get()
+                on:click=move |_| action.run(picked)
+            >
+                {label}
+            </button>
+        }
+    };
+    let always = Signal::stored(true);
+
+    view! {
+        <div class="life-tools">
+            {Tool::iter().map(|option| {
+                view! {
+                    <button
+                        class="tool-button"
+                        class:active=move || tool.get() == option
+                        on:click=move |_| tool.set(option)
+                    >
+                        {option.to_string()}
+                    </button>
+                }
+            }).collect_view()}
+            <span class="tool-separator" />
+            {button("Undo", Action::Undo, always)}
+            {button("Redo", Action::Redo, always)}
+            <span class="tool-separator" />
+            {button("Copy", Action::Copy, has_selection)}
+            {button("Cut", Action::Cut, has_selection)}
+            {button("Paste", Action::Paste, can_paste)}
+            {button("Delete", Action::Delete, has_selection)}
+            {button("Rotate", Action::RotateSelection, has_selection)}
+            {button("Flip H", Action::FlipSelectionHorizontally, has_selection)}
+            {button("Flip V", Action::FlipSelectionVertically, has_selection)}
+        </div>
+    }
+}
@@ -0,0 +1,8 @@
+pub mod encoding;
+pub mod hashlife;
+pub mod library;
+pub mod pattern;
+pub mod rule;
+pub mod soup;
+mod swar;
+pub mod universe;
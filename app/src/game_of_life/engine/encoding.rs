@@ -0,0 +1,222 @@
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, ImageError, Rgba, RgbaImage};
+use strum::{EnumIter, EnumString};
+
+use super::rule::Rule;
+use super::universe::{Selection, UniverseBackend};
+use crate::color::Color;
+
+/// Largest side of an animation, in pixels.
+const MAX_SIDE: u32 = 4096;
+/// Most pixels over all the frames of one recording, which bounds how long
+/// it takes and how big the file gets. The page records on its main thread, so
+/// this is kept to what encodes in a second or two.
+const MAX_PIXELS: u64 = 1 << 24;
+
+/// How a recorded run is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, EnumString, strum::Display)]
+pub enum AnimationFormat {
+    #[default]
+    #[strum(serialize = "GIF")]
+    Gif,
+    #[strum(serialize = "APNG")]
+    Apng,
+}
+
+impl AnimationFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            AnimationFormat::Gif => "gif",
+            AnimationFormat::Apng => "png",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            AnimationFormat::Gif => "image/gif",
+            AnimationFormat::Apng => "image/apng",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnimationError {
+    #[error("a {0}x{1} animation is too large")]
+    TooLarge(u64, u64),
+    #[error("{0} frames of {1} pixels is more than one recording can have")]
+    TooLong(u32, u64),
+    #[error(transparent)]
+    Gif(#[from] ImageError),
+    #[error(transparent)]
+    Apng(#[from] png::EncodingError),
+}
+
+/// What to record and how it looks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationOptions {
+    pub format: AnimationFormat,
+    /// Frames recorded, one per generation.
+    pub generations: u32,
+    /// Pixels a side each cell is drawn as.
+    pub scale: u32,
+    /// How long each frame shows, in milliseconds.
+    pub delay: u16,
+    pub alive: Color,
+    pub dead: Color,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> AnimationOptions {
+        AnimationOptions {
+            format: AnimationFormat::default(),
+            generations: 100,
+            scale: 4,
+            delay: 100,
+            alive: Color {
+                red: 0,
+                green: 0,
+                blue: 0,
+            },
+            dead: Color {
+                red: 255,
+                green: 255,
+                blue: 255,
+            },
+        }
+    }
+}
+
+impl AnimationOptions {
+    /// The colour of a cell in `state` of a rule with `states` states. Dying
+    /// cells fade from the alive colour towards the dead one.
+    pub fn color(&self, state: u8, states: u8) -> Rgba<u8> {
+        if state == Rule::DEAD {
+            return to_rgba(self.dead);
+        }
+        let faded = f32::from(state - 1) / f32::from(states.max(2) - 1);
+        let mix = |alive: u8, dead: u8| {
+            (f32::from(alive) + (f32::from(dead) - f32::from(alive)) * faded).round() as u8
+        };
+        Rgba([
+            mix(self.alive.red, self.dead.red),
+            mix(self.alive.green, self.dead.green),
+            mix(self.alive.blue, self.dead.blue),
+            255,
+        ])
+    }
+}
+
+fn to_rgba(color: Color) -> Rgba<u8> {
+    Rgba([color.red, color.green, color.blue, 255])
+}
+
+/// Checks that `area`, drawn `scale` pixels a cell, fits in `MAX_SIDE`.
+pub fn check_size(area: Selection, scale: u32) -> Result<(), AnimationError> {
+    let (width, height) = (
+        u64::from(area.width) * u64::from(scale),
+        u64::from(area.height) * u64::from(scale),
+    );
+    if width > u64::from(MAX_SIDE) || height > u64::from(MAX_SIDE) {
+        return Err(AnimationError::TooLarge(width, height));
+    }
+    Ok(())
+}
+
+/// The cells of `area` as an image, each cell `scale` pixels a side, which
+/// `check_size` should have passed.
+pub fn frame(
+    universe: &dyn UniverseBackend,
+    area: Selection,
+    options: &AnimationOptions,
+) -> RgbaImage {
+    let states = universe.rule().states();
+    let cells = RgbaImage::from_fn(area.width, area.height, |x, y| {
+        let state = universe.state(area.row + i64::from(y), area.column + i64::from(x));
+        options.color(state, states)
+    });
+    imageops::resize(
+        &cells,
+        area.width * options.scale,
+        area.height * options.scale,
+        FilterType::Nearest,
+    )
+}
+
+/// Records `area` for `options.generations` generations, ticking the
+/// universe after each frame, and encodes the frames as an animation that
+/// loops forever. Each frame is encoded as soon as it is drawn, so only one
+/// is held at a time. Also gives how many generations the universe ran on,
+/// which falls short if encoding fails partway.
+pub fn record(
+    universe: &mut dyn UniverseBackend,
+    area: Selection,
+    options: &AnimationOptions,
+) -> (u32, Result<Vec<u8>, AnimationError>) {
+    if let Err(e) = check_size(area, options.scale) {
+        return (0, Err(e));
+    }
+    let (width, height) = (area.width * options.scale, area.height * options.scale);
+    let pixels = u64::from(width) * u64::from(height);
+    if u64::from(options.generations) * pixels > MAX_PIXELS {
+        return (0, Err(AnimationError::TooLong(options.generations, pixels)));
+    }
+    let mut ticked = 0;
+    let frames = (0..options.generations).map(|_| {
+        let image = frame(universe, area, options);
+        universe.tick();
+        ticked += 1;
+        image
+    });
+    let bytes = match options.format {
+        AnimationFormat::Gif => encode_gif(frames, options.delay),
+        AnimationFormat::Apng => encode_apng(frames, width, height, options.delay),
+    };
+    (ticked, bytes)
+}
+
+fn encode_gif(
+    frames: impl Iterator<Item = RgbaImage>,
+    delay: u16,
+) -> Result<Vec<u8>, AnimationError> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut bytes);
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay = Delay::from_numer_denom_ms(u32::from(delay), 1);
+        for image in frames {
+            encoder.encode_frame(Frame::from_parts(image, 0, 0, delay))?;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Encodes `frames`, each `width` by `height` pixels, as an APNG, which needs
+/// the number of frames before the first.
+fn encode_apng(
+    frames: impl ExactSizeIterator<Item = RgbaImage>,
+    width: u32,
+    height: u32,
+    delay: u16,
+) -> Result<Vec<u8>, AnimationError> {
+    let mut bytes = Vec::new();
+    if frames.len() == 0 {
+        return Ok(bytes);
+    }
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    // Zero plays loops forever.
+    encoder.set_animated(frames.len() as u32, 0)?;
+    encoder.set_frame_delay(delay, 1000)?;
+    let mut writer = encoder.write_header()?;
+    for image in frames {
+        writer.write_image_data(image.as_raw())?;
+    }
+    writer.finish()?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+#[path = "encoding_test.rs"]
+mod encoding_test;
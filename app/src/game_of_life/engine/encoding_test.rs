@@ -0,0 +1,138 @@
+use super::*;
+use crate::game_of_life::engine::soup::Soup;
+use crate::game_of_life::engine::universe::Universe;
+use image::codecs::gif::GifDecoder;
+use image::AnimationDecoder;
+use std::io::Cursor;
+
+const AREA: Selection = Selection {
+    row: 0,
+    column: 0,
+    width: 5,
+    height: 5,
+};
+
+/// A vertical blinker in the middle of a 5x5 grid.
+fn blinker() -> Universe {
+    let mut universe = Universe::new(5, 5, Rule::CONWAY, Soup::new(0, 0.0));
+    for row in 1..4 {
+        universe.set_state(row, 2, Rule::ALIVE);
+    }
+    universe
+}
+
+#[test]
+fn test_frame_scales_cells() {
+    let options = AnimationOptions {
+        scale: 3,
+        ..AnimationOptions::default()
+    };
+    let image = frame(&blinker(), AREA, &options);
+    assert_eq!(image.dimensions(), (15, 15));
+    let black = Rgba([0, 0, 0, 255]);
+    let white = Rgba([255, 255, 255, 255]);
+    assert_eq!(*image.get_pixel(6, 3), black);
+    assert_eq!(*image.get_pixel(8, 11), black);
+    assert_eq!(*image.get_pixel(5, 2), white);
+    assert_eq!(*image.get_pixel(3, 6), white);
+}
+
+#[test]
+fn test_dying_cells_fade() {
+    let options = AnimationOptions::default();
+    assert_eq!(options.color(Rule::ALIVE, 2), Rgba([0, 0, 0, 255]));
+    assert_eq!(options.color(Rule::DEAD, 2), Rgba([255, 255, 255, 255]));
+    // Halfway between alive and dead with three states to go through.
+    assert_eq!(options.color(2, 3), Rgba([128, 128, 128, 255]));
+}
+
+#[test]
+fn test_record_gif() {
+    let mut universe = blinker();
+    let options = AnimationOptions {
+        generations: 4,
+        ..AnimationOptions::default()
+    };
+    let (ticked, bytes) = record(&mut universe, AREA, &options);
+    assert_eq!(ticked, 4);
+    let bytes = bytes.unwrap();
+    assert!(bytes.starts_with(b"GIF89a"));
+
+    let frames = GifDecoder::new(Cursor::new(bytes))
+        .unwrap()
+        .into_frames()
+        .collect_frames()
+        .unwrap();
+    assert_eq!(frames.len(), 4);
+    assert_eq!(frames[0].delay().numer_denom_ms(), (100, 1));
+    let buffer = frames[1].buffer();
+    assert_eq!(buffer.dimensions(), (20, 20));
+    // The blinker lies across on the second frame.
+    assert_eq!(buffer.get_pixel(6, 10).0[..3], [0, 0, 0]);
+    assert_eq!(buffer.get_pixel(10, 2).0[..3], [255, 255, 255]);
+    // Recording ran the universe on a generation per frame.
+    assert_eq!(universe.state(1, 2), Rule::ALIVE);
+    assert_eq!(universe.state(2, 1), Rule::DEAD);
+}
+
+#[test]
+fn test_record_apng() {
+    let options = AnimationOptions {
+        format: AnimationFormat::Apng,
+        generations: 3,
+        scale: 2,
+        delay: 40,
+        ..AnimationOptions::default()
+    };
+    let bytes = record(&mut blinker(), AREA, &options).1.unwrap();
+
+    let mut reader = png::Decoder::new(Cursor::new(bytes)).read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (10, 10));
+    let control = info.animation_control().unwrap();
+    assert_eq!((control.num_frames, control.num_plays), (3, 0));
+    let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+    let mut frames = 0;
+    while reader.next_frame(&mut buffer).is_ok() {
+        frames += 1;
+    }
+    assert_eq!(frames, 3);
+}
+
+#[test]
+fn test_record_refuses_huge_animations() {
+    let mut universe = blinker();
+    let area = Selection {
+        width: 1000,
+        ..AREA
+    };
+    let options = AnimationOptions {
+        scale: 16,
+        ..AnimationOptions::default()
+    };
+    assert!(matches!(
+        record(&mut universe, area, &options),
+        (0, Err(AnimationError::TooLarge(16000, 80)))
+    ));
+    // Nothing ran.
+    assert_eq!(universe.state(2, 1), Rule::DEAD);
+}
+
+#[test]
+fn test_record_refuses_long_animations() {
+    let mut universe = blinker();
+    let area = Selection {
+        width: 256,
+        height: 256,
+        ..AREA
+    };
+    let options = AnimationOptions {
+        scale: 16,
+        ..AnimationOptions::default()
+    };
+    assert!(matches!(
+        record(&mut universe, area, &options),
+        (0, Err(AnimationError::TooLong(100, 16_777_216)))
+    ));
+    assert_eq!(universe.state(2, 1), Rule::DEAD);
+}
@@ -0,0 +1,392 @@
+use std::collections::HashMap;
+
+use super::rule::Rule;
+use super::soup::Soup;
+use super::universe::{Selection, UniverseBackend};
+
+/// Index of a node in `HashLife::nodes`.
+type NodeId = u32;
+
+/// The top left and bottom right cells of a rectangle, as (row, column).
+type Corners = ((i64, i64), (i64, i64));
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Once the arena grows past this many nodes, everything the root no longer
+/// reaches is thrown away.
+const MAX_NODES: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// A node at level `n` covers 2^n x 2^n cells; leaves are single cells.
+    level: u8,
+    /// The north west, north east, south west and south east quadrants.
+    children: [NodeId; 4],
+    population: u64,
+}
+
+/// An unbounded Life plane stored as a hash-consed quadtree, after Gosper's
+/// Hashlife. Identical regions share a node and the future of every node is
+/// memoised, so repetitive patterns can be advanced 2^k generations at once.
+///
+/// Only two-state rules where empty space stays empty are supported.
+pub struct HashLife {
+    width: u32,
+    height: u32,
+    rule: Rule,
+    nodes: Vec<Node>,
+    lookup: HashMap<[NodeId; 4], NodeId>,
+    /// The empty node of each level.
+    empty: Vec<NodeId>,
+    /// The centre of a node advanced 2^j generations, keyed by node and j.
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// Centred on the origin, so a root at level n covers -2^(n-1)..2^(n-1).
+    root: NodeId,
+}
+
+impl HashLife {
+    /// `width` and `height` are the area `soup` and later resets fill,
+    /// starting at the origin.
+    pub fn new(width: u32, height: u32, rule: Rule, soup: Soup) -> HashLife {
+        let mut life = HashLife {
+            width,
+            height,
+            rule,
+            nodes: Vec::new(),
+            lookup: HashMap::new(),
+            empty: Vec::new(),
+            results: HashMap::new(),
+            root: DEAD,
+        };
+        life.clear_nodes();
+        life.reset(soup);
+        life
+    }
+
+    pub fn supports(rule: Rule) -> bool {
+        rule.states() == 2 && rule.next(Rule::DEAD, 0) == Rule::DEAD
+    }
+
+    fn clear_nodes(&mut self) {
+        self.nodes = vec![
+            Node {
+                level: 0,
+                children: [DEAD; 4],
+                population: 0,
+            },
+            Node {
+                level: 0,
+                children: [DEAD; 4],
+                population: 1,
+            },
+        ];
+        self.lookup.clear();
+        self.empty = vec![DEAD];
+        self.results.clear();
+        self.root = self.empty(3);
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.lookup.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.node(children[0]).level + 1,
+            children,
+            population: children.iter().map(|&c| self.node(c).population).sum(),
+        });
+        self.lookup.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = self.empty[self.empty.len() - 1];
+            let id = self.join([below; 4]);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Doubles the root in each direction, keeping the origin in the middle.
+    fn expand(&mut self) {
+        let root = self.node(self.root);
+        let e = self.empty(root.level - 1);
+        let [nw, ne, sw, se] = root.children;
+        let children = [
+            self.join([e, e, e, nw]),
+            self.join([e, e, ne, e]),
+            self.join([e, sw, e, e]),
+            self.join([se, e, e, e]),
+        ];
+        self.root = self.join(children);
+    }
+
+    /// The middle half of a node, one level down.
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.node(id).children.map(|c| self.node(c).children);
+        self.join([nw[3], ne[2], sw[1], se[0]])
+    }
+
+    /// Widens `corners`, the top left and bottom right of the live cells
+    /// found so far, to take in those of node `id`, whose top left cell is at
+    /// `row`, `column`.
+    fn extend_bounds(&self, id: NodeId, row: i64, column: i64, corners: &mut Option<Corners>) {
+        let node = self.node(id);
+        if node.population == 0 {
+            return;
+        }
+        let last = (1i64 << node.level) - 1;
+        if let Some((top_left, bottom_right)) = *corners {
+            // Nothing in a node the corners already cover can widen them.
+            if row >= top_left.0
+                && column >= top_left.1
+                && row + last <= bottom_right.0
+                && column + last <= bottom_right.1
+            {
+                return;
+            }
+        }
+        if node.level == 0 {
+            *corners = Some(match *corners {
+                None => ((row, column), (row, column)),
+                Some((a, b)) => (
+                    (a.0.min(row), a.1.min(column)),
+                    (b.0.max(row), b.1.max(column)),
+                ),
+            });
+            return;
+        }
+        let half = 1i64 << (node.level - 1);
+        for (quadrant, &child) in node.children.iter().enumerate() {
+            let (down, across) = ((quadrant / 2) as i64, (quadrant % 2) as i64);
+            self.extend_bounds(child, row + down * half, column + across * half, corners);
+        }
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        let half = 1i64 << (self.node(self.root).level - 1);
+        (-half..half).contains(&row) && (-half..half).contains(&column)
+    }
+
+    /// Replaces the cell at `row`, `column`, relative to the node's top left.
+    fn set(&mut self, id: NodeId, row: i64, column: i64, leaf: NodeId) -> NodeId {
+        let node = self.node(id);
+        if node.level == 0 {
+            return leaf;
+        }
+        let half = 1i64 << (node.level - 1);
+        let quadrant = (row >= half) as usize * 2 + (column >= half) as usize;
+        let mut children = node.children;
+        children[quadrant] = self.set(children[quadrant], row % half, column % half, leaf);
+        self.join(children)
+    }
+
+    /// The inner 2x2 cells of a 4x4 node after one generation.
+    fn life_4x4(&mut self, id: NodeId) -> NodeId {
+        let quadrants = self.node(id).children.map(|c| self.node(c).children);
+        let alive = |row: usize, col: usize| {
+            quadrants[row / 2 * 2 + col / 2][row % 2 * 2 + col % 2] == ALIVE
+        };
+        let mut children = [DEAD; 4];
+        for (i, child) in children.iter_mut().enumerate() {
+            let (row, col) = (i / 2 + 1, i % 2 + 1);
+            let mut live_neighbors = 0;
+            for neighbor_row in row - 1..=row + 1 {
+                for neighbor_col in col - 1..=col + 1 {
+                    if (neighbor_row, neighbor_col) != (row, col) {
+                        live_neighbors += alive(neighbor_row, neighbor_col) as u8;
+                    }
+                }
+            }
+            let next = self.rule.next(alive(row, col) as u8, live_neighbors);
+            *child = if next == Rule::ALIVE { ALIVE } else { DEAD };
+        }
+        self.join(children)
+    }
+
+    /// The centre of a node advanced 2^j generations, with j capped at the
+    /// node's level less two.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+        let j = j.min(node.level - 2);
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+        let result = if node.population == 0 {
+            self.empty(node.level - 1)
+        } else if node.level == 2 {
+            self.life_4x4(id)
+        } else {
+            let [a, b, c, d] = node.children.map(|c| self.node(c).children);
+            let c1 = self.join(a);
+            let c1 = self.successor(c1, j);
+            let c2 = self.join([a[1], b[0], a[3], b[2]]);
+            let c2 = self.successor(c2, j);
+            let c3 = self.join(b);
+            let c3 = self.successor(c3, j);
+            let c4 = self.join([a[2], a[3], c[0], c[1]]);
+            let c4 = self.successor(c4, j);
+            let c5 = self.join([a[3], b[2], c[1], d[0]]);
+            let c5 = self.successor(c5, j);
+            let c6 = self.join([b[2], b[3], d[0], d[1]]);
+            let c6 = self.successor(c6, j);
+            let c7 = self.join(c);
+            let c7 = self.successor(c7, j);
+            let c8 = self.join([c[1], d[0], c[3], d[2]]);
+            let c8 = self.successor(c8, j);
+            let c9 = self.join(d);
+            let c9 = self.successor(c9, j);
+            let quadrants = [
+                [c1, c2, c4, c5],
+                [c2, c3, c5, c6],
+                [c4, c5, c7, c8],
+                [c5, c6, c8, c9],
+            ];
+            let mut children = [DEAD; 4];
+            for (child, quadrant) in children.iter_mut().zip(quadrants) {
+                *child = if j < node.level - 2 {
+                    // Only half the time is left, which the centres of the
+                    // nine sub-results already account for.
+                    let [nw, ne, sw, se] = quadrant.map(|q| self.node(q).children);
+                    self.join([nw[3], ne[2], sw[1], se[0]])
+                } else {
+                    let quadrant = self.join(quadrant);
+                    self.successor(quadrant, j)
+                };
+            }
+            self.join(children)
+        };
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Rebuilds the arena with just the nodes the root still uses.
+    fn collect_garbage(&mut self) {
+        let old = std::mem::take(&mut self.nodes);
+        let root = self.root;
+        self.clear_nodes();
+        let mut moved = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+        self.root = self.copy(&old, root, &mut moved);
+        log::info!("Hashlife kept {} of {} nodes", self.nodes.len(), old.len());
+    }
+
+    fn copy(&mut self, old: &[Node], id: NodeId, moved: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if let Some(&new) = moved.get(&id) {
+            return new;
+        }
+        let children = old[id as usize].children.map(|c| self.copy(old, c, moved));
+        let new = self.join(children);
+        moved.insert(id, new);
+        new
+    }
+}
+
+impl UniverseBackend for HashLife {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        log::info!("Setting rule to {}", rule);
+        self.rule = rule;
+        self.results.clear();
+    }
+
+    fn state(&self, row: i64, column: i64) -> u8 {
+        if !self.contains(row, column) {
+            return Rule::DEAD;
+        }
+        let mut node = self.node(self.root);
+        let half = 1i64 << (node.level - 1);
+        let (mut row, mut column) = (row + half, column + half);
+        let mut id = self.root;
+        while node.level > 0 {
+            let half = 1i64 << (node.level - 1);
+            id = node.children[(row >= half) as usize * 2 + (column >= half) as usize];
+            (row, column) = (row % half, column % half);
+            node = self.node(id);
+        }
+        if id == ALIVE {
+            Rule::ALIVE
+        } else {
+            Rule::DEAD
+        }
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        let leaf = if state == Rule::DEAD { DEAD } else { ALIVE };
+        if leaf == DEAD && !self.contains(row, column) {
+            return;
+        }
+        while !self.contains(row, column) {
+            self.expand();
+        }
+        let half = 1i64 << (self.node(self.root).level - 1);
+        self.root = self.set(self.root, row + half, column + half, leaf);
+    }
+
+    fn tick(&mut self) {
+        self.step_pow2(0);
+    }
+
+    fn population(&self) -> u64 {
+        self.node(self.root).population
+    }
+
+    fn bounds(&self) -> Option<Selection> {
+        let half = 1i64 << (self.node(self.root).level - 1);
+        let mut corners = None;
+        self.extend_bounds(self.root, -half, -half, &mut corners);
+        corners.map(|(top_left, bottom_right)| Selection::from_corners(top_left, bottom_right))
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        let k = k as u8;
+        // Grow until everything alive sits in the middle quarter, so nothing
+        // can travel out of the centre that `successor` hands back.
+        loop {
+            let level = self.node(self.root).level;
+            let inner = self.centre(self.root);
+            let inner = self.centre(inner);
+            if level >= k + 2 && self.node(inner).population == self.node(self.root).population {
+                break;
+            }
+            self.expand();
+        }
+        self.expand();
+        self.root = self.successor(self.root, k);
+        if self.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+    }
+
+    fn reset(&mut self, soup: Soup) {
+        log::info!("Resetting Universe");
+        self.kill_all();
+        let width = self.width as usize;
+        let cells = soup.cells(width * self.height as usize);
+        for (idx, _) in cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &cell)| cell == Rule::ALIVE)
+        {
+            self.set_state((idx / width) as i64, (idx % width) as i64, Rule::ALIVE);
+        }
+    }
+
+    fn kill_all(&mut self) {
+        log::info!("Clearing Universe");
+        self.root = self.empty(3);
+    }
+}
+
+#[cfg(test)]
+#[path = "hashlife_test.rs"]
+mod hashlife_test;
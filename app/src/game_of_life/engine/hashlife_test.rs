@@ -0,0 +1,112 @@
+use super::*;
+use crate::game_of_life::engine::library;
+use crate::game_of_life::engine::universe::{Selection, Universe};
+
+fn empty_life() -> HashLife {
+    HashLife::new(0, 0, Rule::CONWAY, Soup::new(0, 0.0))
+}
+
+#[test]
+fn test_cells_far_from_the_origin() {
+    let mut life = empty_life();
+    for (row, col) in [(0, 0), (-1, -1), (-1_000_000, 3), (7, 1 << 40)] {
+        assert_eq!(life.state(row, col), Rule::DEAD);
+        life.set_state(row, col, Rule::ALIVE);
+        assert_eq!(life.state(row, col), Rule::ALIVE);
+    }
+    assert_eq!(life.population(), 4);
+    life.toggle_cell(-1, -1);
+    assert_eq!(life.state(-1, -1), Rule::DEAD);
+    assert_eq!(life.population(), 3);
+}
+
+#[test]
+fn test_matches_the_torus() {
+    let mut universe = Universe::new(64, 64, Rule::CONWAY, Soup::new(0, 0.0));
+    for row in 24..40 {
+        for col in 24..40 {
+            if (row * 7 + col * 13) % 5 < 2 {
+                universe.set_state(row, col, Rule::ALIVE);
+            }
+        }
+    }
+    let mut life = empty_life();
+    let selection = Selection {
+        row: 0,
+        column: 0,
+        width: 64,
+        height: 64,
+    };
+    life.insert_pattern(&universe.to_pattern(selection), 0, 0);
+
+    // Short enough that nothing reaches the torus' edges.
+    for generation in 0..16 {
+        assert_eq!(
+            life.to_pattern(selection).cells,
+            universe.to_pattern(selection).cells,
+            "generation {generation}"
+        );
+        universe.tick();
+        life.tick();
+    }
+}
+
+#[test]
+fn test_step_pow2_matches_ticks() {
+    let glider = library::load("Glider").unwrap();
+    let mut stepped = empty_life();
+    stepped.insert_pattern(&glider, 0, 0);
+    let mut ticked = empty_life();
+    ticked.insert_pattern(&glider, 0, 0);
+
+    stepped.step_pow2(5);
+    for _ in 0..32 {
+        ticked.tick();
+    }
+    // A glider moves one cell diagonally every four generations.
+    let selection = Selection {
+        row: 8,
+        column: 8,
+        width: 3,
+        height: 3,
+    };
+    assert_eq!(stepped.to_pattern(selection).cells, glider.cells);
+    assert_eq!(ticked.to_pattern(selection).cells, glider.cells);
+}
+
+#[test]
+fn test_diehard_dies() {
+    let mut life = empty_life();
+    life.insert_pattern(&library::load("Diehard").unwrap(), 0, 0);
+    life.step_pow2(7);
+    assert!(life.population() > 0);
+    life.tick();
+    life.tick();
+    assert_eq!(life.population(), 0);
+}
+
+#[test]
+fn test_r_pentomino_stabilises() {
+    let mut life = empty_life();
+    life.insert_pattern(&library::load("R-pentomino").unwrap(), 0, 0);
+    // 1103 generations, when the pattern settles with six gliders escaping.
+    for k in [10, 6, 3, 2, 1, 0] {
+        life.step_pow2(k);
+    }
+    assert_eq!(life.population(), 116);
+}
+
+#[test]
+fn test_garbage_collection_keeps_the_pattern() {
+    let mut life = empty_life();
+    life.insert_pattern(&library::load("Acorn").unwrap(), 0, 0);
+    life.step_pow2(8);
+    let before = life.population();
+    life.collect_garbage();
+    assert_eq!(life.population(), before);
+    life.step_pow2(8);
+    let mut fresh = empty_life();
+    fresh.insert_pattern(&library::load("Acorn").unwrap(), 0, 0);
+    fresh.step_pow2(9);
+    assert_eq!(life.population(), fresh.population());
+}
@@ -0,0 +1,52 @@
+use super::pattern::Pattern;
+
+/// Classic Conway's Life patterns, as (name, RLE) pairs.
+pub const LIBRARY: &[(&str, &str)] = &[
+    ("Glider", "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"),
+    (
+        "Lightweight spaceship",
+        "x = 5, y = 4, rule = B3/S23\nbo2bo$o$o3bo$4o!",
+    ),
+    (
+        "Middleweight spaceship",
+        "x = 6, y = 5, rule = B3/S23\n3bo$bo3bo$o$o4bo$5o!",
+    ),
+    (
+        "Heavyweight spaceship",
+        "x = 7, y = 5, rule = B3/S23\n3b2o$bo4bo$o$o5bo$6o!",
+    ),
+    (
+        "Pulsar",
+        "x = 13, y = 13, rule = B3/S23\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+         o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+    ("R-pentomino", "x = 3, y = 3, rule = B3/S23\nb2o$2o$bo!"),
+    ("Acorn", "x = 7, y = 3, rule = B3/S23\nbo$3bo$2o2b3o!"),
+    ("Diehard", "x = 8, y = 3, rule = B3/S23\n6bo$2o$bo3b3o!"),
+    (
+        "Gosper glider gun",
+        "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+         2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+    (
+        "Puffer train",
+        "x = 5, y = 18, rule = B3/S23\n3bo$4bo$o3bo$b4o4$o$b2o$2bo$2bo$bo3$3bo$4bo$o3bo$b4o!",
+    ),
+    (
+        "Blinker puffer",
+        "x = 9, y = 18, rule = B3/S23\n3bo$bo3bo$o$o4bo$5o4$b2o$2ob3o$b4o$2b2o2$5b2o$3bo4bo$2bo$\
+         2bo5bo$2b6o!",
+    ),
+];
+
+/// Loads a pattern from the library by name.
+pub fn load(name: &str) -> Option<Pattern> {
+    let (name, rle) = LIBRARY.iter().find(|(n, _)| *n == name)?;
+    let mut pattern = Pattern::from_rle(rle).expect("library patterns are valid RLE");
+    pattern.name = Some(name.to_string());
+    Some(pattern)
+}
+
+#[cfg(test)]
+#[path = "library_test.rs"]
+mod library_test;
@@ -0,0 +1,85 @@
+use super::*;
+use crate::game_of_life::engine::rule::Rule;
+use crate::game_of_life::engine::soup::Soup;
+use crate::game_of_life::engine::universe::{Universe, UniverseBackend};
+
+fn population(universe: &Universe, size: u32) -> usize {
+    (0..(size * size) as usize)
+        .filter(|&i| universe.is_alive(i))
+        .count()
+}
+
+#[test]
+fn test_library_patterns_load() {
+    for (name, _) in LIBRARY {
+        let pattern = load(name).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some(*name));
+        assert_eq!(pattern.rule, Some(Rule::CONWAY));
+        assert!(pattern.cells.contains(&Rule::ALIVE), "{name} is empty");
+    }
+    assert!(load("Unknown").is_none());
+}
+
+#[test]
+fn test_spaceships_keep_their_shape() {
+    for name in [
+        "Glider",
+        "Lightweight spaceship",
+        "Middleweight spaceship",
+        "Heavyweight spaceship",
+    ] {
+        let pattern = load(name).unwrap();
+        let alive = pattern.cells.iter().filter(|&&s| s == Rule::ALIVE).count();
+        let mut universe = Universe::new(32, 32, Rule::CONWAY, Soup::new(0, 0.0));
+        universe.insert_pattern(&pattern, 12, 12);
+        for _ in 0..4 {
+            universe.tick();
+        }
+        assert_eq!(population(&universe, 32), alive, "{name}");
+    }
+}
+
+#[test]
+fn test_diehard_dies() {
+    let mut universe = Universe::new(64, 64, Rule::CONWAY, Soup::new(0, 0.0));
+    universe.insert_pattern(&load("Diehard").unwrap(), 30, 28);
+    for _ in 0..130 {
+        universe.tick();
+    }
+    assert_eq!(population(&universe, 64), 0);
+}
+
+#[test]
+fn test_puffers_move_and_leave_debris() {
+    for name in ["Puffer train", "Blinker puffer"] {
+        let pattern = load(name).unwrap();
+        let (top, left) = (56, 56);
+        let (bottom, right) = (top + pattern.height, left + pattern.width);
+        let mut universe = Universe::new(128, 128, Rule::CONWAY, Soup::new(0, 0.0));
+        universe.insert_pattern(&pattern, top as i64, left as i64);
+        for _ in 0..100 {
+            universe.tick();
+        }
+        let cells: Vec<(u32, u32)> = (0..128 * 128)
+            .filter(|&i| universe.is_alive(i as usize))
+            .map(|i| (i / 128, i % 128))
+            .collect();
+        let furthest = cells
+            .iter()
+            .map(|&(row, col)| {
+                (top.saturating_sub(row))
+                    .max((row + 1).saturating_sub(bottom))
+                    .max(left.saturating_sub(col))
+                    .max((col + 1).saturating_sub(right))
+            })
+            .max()
+            .unwrap();
+        assert!(furthest >= 45, "{name} only got {furthest} cells away");
+        assert!(
+            cells
+                .iter()
+                .any(|&(row, col)| (top..bottom).contains(&row) && (left..right).contains(&col)),
+            "{name} left nothing behind"
+        );
+    }
+}
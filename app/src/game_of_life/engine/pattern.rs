@@ -0,0 +1,357 @@
+use std::fmt::Write;
+
+use super::rule::{Rule, RuleError};
+
+/// A rectangle of cell states that can be loaded from and saved to the RLE and
+/// plaintext (`.cells`) formats used by other Life programs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major cell states, see `Rule`.
+    pub cells: Vec<u8>,
+    /// The rule the pattern was written for, if the file says.
+    pub rule: Option<Rule>,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PatternError {
+    #[error("invalid RLE header {0:?}")]
+    Header(String),
+    #[error(transparent)]
+    Rule(#[from] RuleError),
+    #[error("unexpected {0:?} in pattern")]
+    UnexpectedChar(char),
+    #[error("cell state {0} is out of range")]
+    State(u32),
+    #[error("the pattern has no cells")]
+    Empty,
+    #[error("a {0}×{1} pattern is more than the {MAX_CELLS} cells that can be loaded")]
+    TooLarge(u64, u64),
+    #[error("run count is too large")]
+    Count,
+}
+
+/// RLE lines are kept to this length, like most other writers do.
+const RLE_LINE_LENGTH: usize = 70;
+/// The most cells a loaded pattern may cover. A few bytes of RLE can declare
+/// or run-length encode far more than fits in memory.
+pub const MAX_CELLS: u64 = 1 << 24;
+
+fn check_size(width: u64, height: u64) -> Result<(), PatternError> {
+    if width.saturating_mul(height) > MAX_CELLS {
+        Err(PatternError::TooLarge(width, height))
+    } else {
+        Ok(())
+    }
+}
+
+impl Pattern {
+    pub fn new(width: u32, height: u32) -> Pattern {
+        Pattern {
+            name: None,
+            width,
+            height,
+            cells: vec![Rule::DEAD; width as usize * height as usize],
+            rule: None,
+        }
+    }
+
+    pub fn get(&self, row: u32, column: u32) -> u8 {
+        self.cells[row as usize * self.width as usize + column as usize]
+    }
+
+    /// A pattern of the given size where each cell is copied from `self` at the
+    /// position `source` maps it to.
+    fn transformed(
+        &self,
+        width: u32,
+        height: u32,
+        source: impl Fn(u32, u32) -> (u32, u32),
+    ) -> Pattern {
+        let mut pattern = Pattern {
+            width,
+            height,
+            ..self.clone()
+        };
+        for row in 0..height {
+            for col in 0..width {
+                let (source_row, source_col) = source(row, col);
+                pattern.cells[(row * width + col) as usize] = self.get(source_row, source_col);
+            }
+        }
+        pattern
+    }
+
+    pub fn rotate_clockwise(&self) -> Pattern {
+        self.transformed(self.height, self.width, |row, col| {
+            (self.height - 1 - col, row)
+        })
+    }
+
+    /// Mirrors the pattern left to right.
+    pub fn flip_horizontal(&self) -> Pattern {
+        self.transformed(self.width, self.height, |row, col| {
+            (row, self.width - 1 - col)
+        })
+    }
+
+    /// Mirrors the pattern top to bottom.
+    pub fn flip_vertical(&self) -> Pattern {
+        self.transformed(self.width, self.height, |row, col| {
+            (self.height - 1 - row, col)
+        })
+    }
+
+    /// Builds a pattern from the live cells found while parsing, sized to fit
+    /// both them and the dimensions the file declared.
+    fn from_cells(cells: &[(u32, u32, u8)], width: u32, height: u32) -> Pattern {
+        let width = cells
+            .iter()
+            .map(|&(_, col, _)| col + 1)
+            .fold(width, u32::max);
+        let height = cells
+            .iter()
+            .map(|&(row, _, _)| row + 1)
+            .fold(height, u32::max);
+        let mut pattern = Pattern::new(width, height);
+        for &(row, col, state) in cells {
+            pattern.cells[(row * width + col) as usize] = state;
+        }
+        pattern
+    }
+
+    /// Parses either format, telling them apart by their first line.
+    pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+        let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+        match first {
+            None => Err(PatternError::Empty),
+            Some(line) if line.starts_with('!') || line.chars().all(|c| ".Oo*".contains(c)) => {
+                Pattern::from_plaintext(text)
+            }
+            Some(_) => Pattern::from_rle(text),
+        }
+    }
+
+    pub fn from_rle(text: &str) -> Result<Pattern, PatternError> {
+        let mut name = None;
+        let (mut width, mut height, mut rule) = (0, 0, None);
+        let mut body = String::new();
+        for line in text.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(n) = comment.strip_prefix('N') {
+                    name = Some(n.trim().to_string());
+                }
+            } else if body.is_empty() && line.starts_with('x') {
+                // Golly's bounded grids follow the rule with a topology,
+                // such as `:T100,100`, which is ignored.
+                let header = line.split(':').next().unwrap_or(line);
+                for field in header.split(',') {
+                    let (key, value) = field
+                        .split_once('=')
+                        .ok_or_else(|| PatternError::Header(line.to_string()))?;
+                    let value = value.trim();
+                    let size = || {
+                        value
+                            .parse()
+                            .map_err(|_| PatternError::Header(line.to_string()))
+                    };
+                    match key.trim() {
+                        "x" => width = size()?,
+                        "y" => height = size()?,
+                        "rule" => rule = Some(value.parse()?),
+                        _ => {}
+                    }
+                }
+                check_size(u64::from(width), u64::from(height))?;
+            } else {
+                body.push_str(line);
+            }
+        }
+
+        let mut cells = Vec::new();
+        let (mut row, mut col) = (0u32, 0u32);
+        // The far edges of the live cells so far.
+        let (mut right, mut bottom) = (u64::from(width), u64::from(height));
+        let mut count = None::<u32>;
+        let mut prefix = None::<char>;
+        for c in body.chars() {
+            let state = match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap();
+                    count = Some(
+                        count
+                            .unwrap_or(0)
+                            .checked_mul(10)
+                            .and_then(|count| count.checked_add(digit))
+                            .ok_or(PatternError::Count)?,
+                    );
+                    continue;
+                }
+                'p'..='y' if prefix.is_none() => {
+                    prefix = Some(c);
+                    continue;
+                }
+                'b' | '.' => Rule::DEAD as u32,
+                'o' => Rule::ALIVE as u32,
+                'A'..='X' => {
+                    let high = prefix.map_or(0, |p| (p as u32 - 'p' as u32 + 1) * 24);
+                    high + (c as u32 - 'A' as u32 + 1)
+                }
+                '$' => {
+                    row = row.saturating_add(count.take().unwrap_or(1));
+                    col = 0;
+                    continue;
+                }
+                '!' => break,
+                c if c.is_whitespace() => continue,
+                c => return Err(PatternError::UnexpectedChar(c)),
+            };
+            let state = u8::try_from(state).map_err(|_| PatternError::State(state))?;
+            let run = count.take().unwrap_or(1);
+            let end = col.saturating_add(run);
+            if state != Rule::DEAD {
+                right = right.max(u64::from(end));
+                bottom = bottom.max(u64::from(row) + 1);
+                check_size(right, bottom)?;
+                cells.extend((col..end).map(|col| (row, col, state)));
+            }
+            col = end;
+            prefix = None;
+        }
+
+        let mut pattern = Pattern::from_cells(&cells, width, height);
+        pattern.name = name;
+        pattern.rule = rule;
+        Ok(pattern)
+    }
+
+    pub fn from_plaintext(text: &str) -> Result<Pattern, PatternError> {
+        let mut name = None;
+        let mut cells = Vec::new();
+        let mut row = 0;
+        let mut right = 0;
+        for line in text.lines().map(str::trim_end) {
+            if let Some(comment) = line.strip_prefix('!') {
+                if let Some(n) = comment.strip_prefix("Name:") {
+                    name = Some(n.trim().to_string());
+                }
+                continue;
+            }
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '.' => {}
+                    'O' | 'o' | '*' => cells.push((row, col as u32, Rule::ALIVE)),
+                    c => return Err(PatternError::UnexpectedChar(c)),
+                }
+            }
+            row += 1;
+            if let Some(&(_, col, _)) = cells.last() {
+                right = right.max(u64::from(col) + 1);
+                check_size(right, u64::from(row))?;
+            }
+        }
+        let mut pattern = Pattern::from_cells(&cells, 0, row);
+        pattern.name = name;
+        Ok(pattern)
+    }
+
+    pub fn to_rle(&self) -> String {
+        let mut rle = String::new();
+        if let Some(name) = &self.name {
+            writeln!(rle, "#N {name}").unwrap();
+        }
+        write!(rle, "x = {}, y = {}", self.width, self.height).unwrap();
+        if let Some(rule) = self.rule {
+            write!(rle, ", rule = {rule}").unwrap();
+        }
+        rle.push('\n');
+
+        let multi_state = self.rule.is_some_and(|rule| rule.states() > 2)
+            || self.cells.iter().any(|&state| state > Rule::ALIVE);
+        let mut runs = Vec::new();
+        let mut previous_row = None;
+        for row in 0..self.height {
+            let states: Vec<u8> = (0..self.width).map(|col| self.get(row, col)).collect();
+            let Some(last) = states.iter().rposition(|&state| state != Rule::DEAD) else {
+                continue;
+            };
+            let skipped = row - previous_row.unwrap_or(0);
+            if skipped > 0 {
+                runs.push(rle_rows(skipped));
+            }
+            previous_row = Some(row);
+            for state in states[..=last].chunk_by(|a, b| a == b) {
+                runs.push(rle_cells(state.len() as u32, state[0], multi_state));
+            }
+        }
+        runs.push("!".to_string());
+
+        let mut line_length = 0;
+        for run in runs {
+            if line_length + run.len() > RLE_LINE_LENGTH {
+                rle.push('\n');
+                line_length = 0;
+            }
+            line_length += run.len();
+            rle.push_str(&run);
+        }
+        rle.push('\n');
+        rle
+    }
+
+    pub fn to_plaintext(&self) -> String {
+        let mut text = String::new();
+        if let Some(name) = &self.name {
+            writeln!(text, "!Name: {name}").unwrap();
+        }
+        for row in 0..self.height {
+            for col in 0..self.width {
+                text.push(if self.get(row, col) == Rule::DEAD {
+                    '.'
+                } else {
+                    'O'
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn rle_count(count: u32) -> String {
+    if count == 1 {
+        String::new()
+    } else {
+        count.to_string()
+    }
+}
+
+fn rle_rows(count: u32) -> String {
+    format!("{}$", rle_count(count))
+}
+
+/// Two state patterns use `b` and `o`, others `.` and `A` to `X` with a `p` to
+/// `y` prefix for states past 24.
+fn rle_cells(count: u32, state: u8, multi_state: bool) -> String {
+    let tag = match (state, multi_state) {
+        (Rule::DEAD, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (Rule::DEAD, true) => ".".to_string(),
+        (state, true) => {
+            let (high, low) = ((state - 1) / 24, (state - 1) % 24);
+            let mut tag = String::new();
+            if high > 0 {
+                tag.push((b'p' + high - 1) as char);
+            }
+            tag.push((b'A' + low) as char);
+            tag
+        }
+    };
+    format!("{}{tag}", rle_count(count))
+}
+
+#[cfg(test)]
+#[path = "pattern_test.rs"]
+mod pattern_test;
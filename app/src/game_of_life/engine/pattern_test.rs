@@ -0,0 +1,176 @@
+use super::*;
+
+const GLIDER_RLE: &str = "#N Glider
+#C A comment
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!
+";
+
+const GLIDER_CELLS: &str = "!Name: Glider
+!
+.O.
+..O
+OOO
+";
+
+fn glider() -> Pattern {
+    let mut glider = Pattern::new(3, 3);
+    for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        glider.cells[row * 3 + col] = Rule::ALIVE;
+    }
+    glider.name = Some("Glider".to_string());
+    glider
+}
+
+#[test]
+fn test_parse_rle() {
+    let pattern = Pattern::from_rle(GLIDER_RLE).unwrap();
+    assert_eq!(
+        pattern,
+        Pattern {
+            rule: Some(Rule::CONWAY),
+            ..glider()
+        }
+    );
+}
+
+#[test]
+fn test_parse_plaintext() {
+    assert_eq!(Pattern::from_plaintext(GLIDER_CELLS), Ok(glider()));
+}
+
+#[test]
+fn test_parse_detects_format() {
+    assert_eq!(Pattern::parse(GLIDER_CELLS), Ok(glider()));
+    assert_eq!(Pattern::parse(".O\nO.").unwrap().height, 2);
+    assert_eq!(Pattern::parse(GLIDER_RLE).unwrap().rule, Some(Rule::CONWAY));
+    assert_eq!(Pattern::parse(" \n"), Err(PatternError::Empty));
+}
+
+#[test]
+fn test_rle_round_trip() {
+    let rle = Pattern {
+        rule: Some(Rule::CONWAY),
+        ..glider()
+    }
+    .to_rle();
+    // Trailing dead cells are left out of each row.
+    assert_eq!(rle, "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    assert_eq!(Pattern::from_rle(&rle), Pattern::from_rle(GLIDER_RLE));
+}
+
+#[test]
+fn test_plaintext_round_trip() {
+    let text = glider().to_plaintext();
+    assert_eq!(Pattern::from_plaintext(&text), Ok(glider()));
+}
+
+#[test]
+fn test_rle_blank_rows_and_size() {
+    // Rows 0 and 2 are blank, and the header is wider than the cells.
+    let pattern = Pattern::from_rle("x = 5, y = 4\n$2o2$o!").unwrap();
+    assert_eq!((pattern.width, pattern.height), (5, 4));
+    assert_eq!(pattern.get(1, 1), Rule::ALIVE);
+    assert_eq!(pattern.get(3, 0), Rule::ALIVE);
+    assert_eq!(pattern.cells.iter().filter(|&&s| s != 0).count(), 3);
+    assert_eq!(pattern.to_rle(), "x = 5, y = 4\n$2o2$o!\n");
+}
+
+#[test]
+fn test_rle_multi_state() {
+    let pattern = Pattern::from_rle("x = 4, y = 1, rule = B2/S/C3\n.A2B!").unwrap();
+    assert_eq!(pattern.cells, vec![0, 1, 2, 2]);
+    assert_eq!(pattern.to_rle(), "x = 4, y = 1, rule = B2/S/C3\n.A2B!\n");
+
+    let high = Pattern::from_rle("pAyO!").unwrap();
+    assert_eq!(high.cells, vec![25, 255]);
+    assert!(high.to_rle().ends_with("pAyO!\n"));
+    assert_eq!(Pattern::from_rle("yP!"), Err(PatternError::State(256)));
+}
+
+#[test]
+fn test_rle_wraps_long_lines() {
+    let mut pattern = Pattern::new(200, 1);
+    for col in (0..200).step_by(2) {
+        pattern.cells[col] = Rule::ALIVE;
+    }
+    let rle = pattern.to_rle();
+    assert!(rle.lines().all(|line| line.len() <= RLE_LINE_LENGTH));
+    assert_eq!(Pattern::from_rle(&rle), Ok(pattern));
+}
+
+#[test]
+fn test_pattern_transforms() {
+    // .O.
+    // ..O
+    // OOO
+    let glider = glider();
+    assert_eq!(
+        glider.rotate_clockwise().to_plaintext(),
+        "!Name: Glider\nO..\nO.O\nOO.\n"
+    );
+    assert_eq!(
+        glider.flip_horizontal().to_plaintext(),
+        "!Name: Glider\n.O.\nO..\nOOO\n"
+    );
+    assert_eq!(
+        glider.flip_vertical().to_plaintext(),
+        "!Name: Glider\nOOO\n..O\n.O.\n"
+    );
+
+    let wide = Pattern::from_rle("x = 3, y = 1\n2o!").unwrap();
+    let tall = wide.rotate_clockwise();
+    assert_eq!((tall.width, tall.height), (1, 3));
+    assert_eq!(tall.cells, vec![1, 1, 0]);
+    let full_turn = (0..4).fold(glider.clone(), |p, _| p.rotate_clockwise());
+    assert_eq!(full_turn, glider);
+}
+
+#[test]
+fn test_pattern_errors() {
+    assert_eq!(
+        Pattern::from_rle("x = a, y = 1\no!"),
+        Err(PatternError::Header("x = a, y = 1".to_string()))
+    );
+    assert_eq!(
+        Pattern::from_rle("x = 1, y = 1, rule = B9/S\no!"),
+        Err(PatternError::Rule(RuleError::Count('9')))
+    );
+    assert_eq!(
+        Pattern::from_rle("2z!"),
+        Err(PatternError::UnexpectedChar('z'))
+    );
+    assert_eq!(
+        Pattern::from_plaintext(".O\n.X"),
+        Err(PatternError::UnexpectedChar('X'))
+    );
+}
+
+#[test]
+fn test_pattern_size_limits() {
+    assert_eq!(
+        Pattern::from_rle("x = 70000, y = 70000\no!"),
+        Err(PatternError::TooLarge(70000, 70000))
+    );
+    assert_eq!(
+        Pattern::from_rle("999999999o!"),
+        Err(PatternError::TooLarge(999999999, 1))
+    );
+    assert_eq!(
+        Pattern::from_rle("o16777216$o!"),
+        Err(PatternError::TooLarge(1, 16777217))
+    );
+    assert_eq!(Pattern::from_rle("99999999999o!"), Err(PatternError::Count));
+    let wide = format!("{}\n{}O", "O".repeat(5000), ".\n".repeat(4000));
+    assert!(matches!(
+        Pattern::from_plaintext(&wide),
+        Err(PatternError::TooLarge(5000, _))
+    ));
+}
+
+#[test]
+fn test_rle_ignores_topology() {
+    let pattern = Pattern::from_rle("x = 3, y = 3, rule = B3/S23:T100,100\nbob$2bo$3o!").unwrap();
+    assert_eq!(pattern.rule, Some(Rule::CONWAY));
+    assert_eq!(pattern.cells, glider().cells);
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A Life-like or Generations cellular automaton rule: which live neighbour
+/// counts bring a dead cell to life, which keep a live cell alive, and how many
+/// states a cell passes through on its way from alive to dead.
+///
+/// Cell states are numbered `0` for dead, `1` for alive and `2..states` for
+/// dying. Only live cells count as neighbours, and a dying cell always moves on
+/// to the next state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// Bit `n` is set if a dead cell with `n` live neighbours is born.
+    birth: u16,
+    /// Bit `n` is set if a live cell with `n` live neighbours survives.
+    survival: u16,
+    /// 2 for Life-like rules, where cells die immediately.
+    states: u8,
+}
+
+/// Well known rules, as (name, rule string) pairs.
+pub const PRESETS: &[(&str, &str)] = &[
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Day & Night", "B3678/S34678"),
+    ("Seeds", "B2/S"),
+    ("Life without Death", "B3/S012345678"),
+    ("Maze", "B3/S12345"),
+    ("Replicator", "B1357/S1357"),
+    ("2x2", "B36/S125"),
+    ("Brian's Brain", "B2/S/C3"),
+    ("Star Wars", "B2/S345/C4"),
+    ("Frogs", "B34/S12/C3"),
+];
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum RuleError {
+    #[error("expected a rule like B3/S23")]
+    Format,
+    #[error("neighbour counts must be digits from 0 to 8, found {0:?}")]
+    Count(char),
+    #[error("neighbour count {0} is listed twice")]
+    Duplicate(char),
+    #[error("the number of states must be from 2 to 255, found {0:?}")]
+    States(String),
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+    };
+
+    pub const DEAD: u8 = 0;
+    pub const ALIVE: u8 = 1;
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The state of a cell in the next generation.
+    pub fn next(&self, state: u8, live_neighbors: u8) -> u8 {
+        match state {
+            Rule::DEAD if self.born(live_neighbors) => Rule::ALIVE,
+            Rule::ALIVE if self.survives(live_neighbors) => Rule::ALIVE,
+            _ => self.fade(state),
+        }
+    }
+
+    /// Whether a dead cell with this many live neighbours comes to life.
+    pub fn born(&self, live_neighbors: u8) -> bool {
+        self.birth & 1 << live_neighbors != 0
+    }
+
+    /// Whether a live cell with this many live neighbours stays alive.
+    pub fn survives(&self, live_neighbors: u8) -> bool {
+        self.survival & 1 << live_neighbors != 0
+    }
+
+    /// The next state of a cell that is neither born nor survives: dead
+    /// cells stay dead, and live and dying ones move on towards death.
+    pub fn fade(&self, state: u8) -> u8 {
+        match state {
+            Rule::DEAD => Rule::DEAD,
+            dying if dying < self.states - 1 => dying + 1,
+            _ => Rule::DEAD,
+        }
+    }
+
+    fn parse_counts(counts: &str) -> Result<u16, RuleError> {
+        counts.chars().try_fold(0u16, |mask, c| {
+            let bit = match c.to_digit(10) {
+                Some(n) if n <= 8 => 1 << n,
+                _ => return Err(RuleError::Count(c)),
+            };
+            if mask & bit != 0 {
+                return Err(RuleError::Duplicate(c));
+            }
+            Ok(mask | bit)
+        })
+    }
+
+    fn parse_states(states: &str) -> Result<u8, RuleError> {
+        match states.parse::<u8>() {
+            Ok(states) if states >= 2 => Ok(states),
+            _ => Err(RuleError::States(states.to_string())),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+/// Accepts `B36/S23` and `B2/S/C3` with the parts in any order and any case,
+/// as well as the older `23/36` and `/2/3` survival/birth/states notation.
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(RuleError::Format);
+        }
+
+        let prefixed = |part: &&str| part.starts_with(|c: char| c.is_ascii_alphabetic());
+        let (birth, survival, states) = if parts.iter().all(prefixed) {
+            let (mut birth, mut survival, mut states) = (None, None, None);
+            for part in parts {
+                let slot = match part.as_bytes()[0].to_ascii_uppercase() {
+                    b'B' => &mut birth,
+                    b'S' => &mut survival,
+                    b'C' | b'G' => &mut states,
+                    _ => return Err(RuleError::Format),
+                };
+                if slot.replace(&part[1..]).is_some() {
+                    return Err(RuleError::Format);
+                }
+            }
+            match (birth, survival) {
+                (Some(birth), Some(survival)) => (birth, survival, states),
+                _ => return Err(RuleError::Format),
+            }
+        } else if parts.iter().any(prefixed) {
+            return Err(RuleError::Format);
+        } else {
+            (parts[1], parts[0], parts.get(2).copied())
+        };
+
+        Ok(Rule {
+            birth: Rule::parse_counts(birth)?,
+            survival: Rule::parse_counts(survival)?,
+            states: states.map_or(Ok(2), Rule::parse_states)?,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |mask: u16| {
+            (0..=8)
+                .filter(|n| mask & (1 << n) != 0)
+                .map(|n| char::from(b'0' + n))
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "rule_test.rs"]
+mod rule_test;
@@ -0,0 +1,77 @@
+use super::*;
+
+#[test]
+fn test_conway_rule() {
+    let rule: Rule = "B3/S23".parse().unwrap();
+    assert_eq!(rule, Rule::CONWAY);
+    assert_eq!(rule.next(Rule::DEAD, 3), Rule::ALIVE);
+    assert_eq!(rule.next(Rule::DEAD, 2), Rule::DEAD);
+    assert_eq!(rule.next(Rule::ALIVE, 2), Rule::ALIVE);
+    assert_eq!(rule.next(Rule::ALIVE, 3), Rule::ALIVE);
+    assert_eq!(rule.next(Rule::ALIVE, 1), Rule::DEAD);
+    assert_eq!(rule.next(Rule::ALIVE, 4), Rule::DEAD);
+}
+
+#[test]
+fn test_rule_notations() {
+    let highlife = Rule::from_str("B36/S23").unwrap();
+    assert_eq!(Rule::from_str("b36/s23"), Ok(highlife));
+    assert_eq!(Rule::from_str("S23/B36"), Ok(highlife));
+    assert_eq!(Rule::from_str("23/36"), Ok(highlife));
+    assert_eq!(Rule::from_str(" B36/S23 "), Ok(highlife));
+}
+
+#[test]
+fn test_rule_empty_counts() {
+    let seeds = Rule::from_str("B2/S").unwrap();
+    assert_eq!(seeds.next(Rule::DEAD, 2), Rule::ALIVE);
+    assert!((0..=8).all(|n| seeds.next(Rule::ALIVE, n) == Rule::DEAD));
+}
+
+#[test]
+fn test_generations_rule() {
+    let brain = Rule::from_str("B2/S/C3").unwrap();
+    assert_eq!(brain.states(), 3);
+    assert_eq!(Rule::from_str("/2/3"), Ok(brain));
+    assert_eq!(Rule::from_str("c3/b2/s"), Ok(brain));
+    assert_eq!(Rule::from_str("B2/S/G3"), Ok(brain));
+
+    assert_eq!(brain.next(Rule::DEAD, 2), Rule::ALIVE);
+    // Live cells start dying, and dying cells ignore their neighbours.
+    assert_eq!(brain.next(Rule::ALIVE, 2), 2);
+    assert_eq!(brain.next(2, 2), Rule::DEAD);
+}
+
+#[test]
+fn test_generations_last_state() {
+    let rule = Rule::from_str("B3/S23/C255").unwrap();
+    assert_eq!(rule.next(254, 3), Rule::DEAD);
+    assert_eq!(rule.next(253, 3), 254);
+}
+
+#[test]
+fn test_rule_errors() {
+    assert_eq!(Rule::from_str("B3S23"), Err(RuleError::Format));
+    assert_eq!(Rule::from_str("B39/S23"), Err(RuleError::Count('9')));
+    assert_eq!(Rule::from_str("B3/S2x"), Err(RuleError::Count('x')));
+    assert_eq!(Rule::from_str("B33/S23"), Err(RuleError::Duplicate('3')));
+    assert_eq!(Rule::from_str("B3/23"), Err(RuleError::Format));
+    assert_eq!(Rule::from_str("B3/B3"), Err(RuleError::Format));
+    assert_eq!(Rule::from_str("B3/S23/C3/C3"), Err(RuleError::Format));
+    assert_eq!(
+        Rule::from_str("B2/S/C1"),
+        Err(RuleError::States("1".to_string()))
+    );
+    assert_eq!(
+        Rule::from_str("B2/S/C"),
+        Err(RuleError::States(String::new()))
+    );
+}
+
+#[test]
+fn test_presets_round_trip() {
+    for (name, notation) in PRESETS {
+        let rule = Rule::from_str(notation).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(rule.to_string(), *notation);
+    }
+}
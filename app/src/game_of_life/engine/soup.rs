@@ -0,0 +1,78 @@
+use rand::distr::{Bernoulli, Distribution};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Chance of each cell starting alive when the URL doesn't say.
+pub const DEFAULT_DENSITY: f64 = 0.5;
+
+/// A random fill of the universe, given by a seed and the chance of each
+/// cell starting alive, so the same soup can be made again and shared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    seed: u64,
+    density: f64,
+}
+
+impl Soup {
+    /// Densities outside 0 to 1 are clamped, and one that isn't a number is
+    /// taken as the default.
+    pub fn new(seed: u64, density: f64) -> Soup {
+        let density = if density.is_nan() {
+            DEFAULT_DENSITY
+        } else {
+            density.clamp(0.0, 1.0)
+        };
+        Soup { seed, density }
+    }
+
+    /// A soup of the default density with a fresh seed, kept short enough to
+    /// read out of a URL.
+    pub fn random() -> Soup {
+        Soup::new(u64::from(rand::random::<u32>()), DEFAULT_DENSITY)
+    }
+
+    /// The soup from the `seed` and `density` query parameters, with a fresh
+    /// seed when there isn't one.
+    pub fn from_query(seed: Option<&str>, density: Option<&str>) -> Soup {
+        let random = Soup::random();
+        Soup::new(
+            seed.and_then(|seed| seed.parse().ok())
+                .unwrap_or(random.seed),
+            density
+                .and_then(|density| density.parse().ok())
+                .unwrap_or(DEFAULT_DENSITY),
+        )
+    }
+
+    /// The query string that makes this soup again.
+    pub fn to_query(self) -> String {
+        format!("?seed={}&density={}", self.seed, self.density)
+    }
+
+    pub fn seed(self) -> u64 {
+        self.seed
+    }
+
+    pub fn density(self) -> f64 {
+        self.density
+    }
+
+    /// The soup after this one, at the same density.
+    pub fn next(self) -> Soup {
+        Soup {
+            seed: self.seed.wrapping_add(1),
+            ..self
+        }
+    }
+
+    /// The starting states of `count` cells, row by row.
+    pub fn cells(self, count: usize) -> Vec<u8> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let alive = Bernoulli::new(self.density).expect("density is between 0 and 1");
+        (0..count).map(|_| alive.sample(&mut rng) as u8).collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "soup_test.rs"]
+mod soup_test;
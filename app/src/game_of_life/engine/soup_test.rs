@@ -0,0 +1,50 @@
+use super::*;
+
+#[test]
+fn test_same_seed_gives_same_soup() {
+    let soup = Soup::new(42, 0.3);
+    assert_eq!(soup.cells(4096), soup.cells(4096));
+    assert_ne!(soup.cells(4096), soup.next().cells(4096));
+}
+
+#[test]
+fn test_density_is_respected() {
+    assert!(Soup::new(7, 0.0).cells(1000).iter().all(|&cell| cell == 0));
+    assert!(Soup::new(7, 1.0).cells(1000).iter().all(|&cell| cell == 1));
+
+    let alive = Soup::new(7, 0.25)
+        .cells(10_000)
+        .iter()
+        .filter(|&&cell| cell == 1)
+        .count();
+    assert!((2_300..2_700).contains(&alive), "{alive} cells alive");
+}
+
+#[test]
+fn test_density_is_clamped() {
+    assert_eq!(Soup::new(1, 1.5).density(), 1.0);
+    assert_eq!(Soup::new(1, -0.5).density(), 0.0);
+    assert_eq!(Soup::new(1, f64::NAN).density(), DEFAULT_DENSITY);
+}
+
+#[test]
+fn test_query_round_trip() {
+    let soup = Soup::new(1234, 0.35);
+    assert_eq!(soup.to_query(), "?seed=1234&density=0.35");
+    assert_eq!(Soup::from_query(Some("1234"), Some("0.35")), soup);
+}
+
+#[test]
+fn test_query_defaults() {
+    let soup = Soup::from_query(None, Some("nonsense"));
+    assert_eq!(soup.density(), DEFAULT_DENSITY);
+    assert_eq!(
+        Soup::from_query(Some("9"), None),
+        Soup::new(9, DEFAULT_DENSITY)
+    );
+}
+
+#[test]
+fn test_next_seed_wraps() {
+    assert_eq!(Soup::new(u64::MAX, 0.5).next().seed(), 0);
+}
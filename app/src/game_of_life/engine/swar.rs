@@ -0,0 +1,149 @@
+use super::rule::Rule;
+
+/// The count of live neighbours of 64 cells, bit-sliced: word `i` holds bit
+/// `i` of every cell's count.
+type Count = [u64; 4];
+
+/// Works out the next generation of a `width` by `height` grid of `cells`
+/// into `next`, 64 cells at a time. Cells beyond the edges wrap around when
+/// `wrap` is set, and are dead otherwise.
+pub fn step(cells: &[u8], next: &mut [u8], width: u32, height: u32, rule: Rule, wrap: bool) {
+    let (width, height) = (width as usize, height as usize);
+    if width == 0 {
+        return;
+    }
+    let words = width.div_ceil(64);
+    // The live cells, a row of words at a time, and for Generations rules the
+    // dying ones, which can't be born.
+    let multi_state = rule.states() > 2;
+    let mut alive = vec![0u64; words * height];
+    let mut dying = vec![0u64; if multi_state { words * height } else { 0 }];
+    for (row, cells) in cells.chunks_exact(width).enumerate() {
+        for (word, cells) in cells.chunks(64).enumerate() {
+            let word = row * words + word;
+            alive[word] = pack(cells, |cell| cell == Rule::ALIVE);
+            if multi_state {
+                dying[word] = pack(cells, |cell| cell > Rule::ALIVE);
+            }
+        }
+    }
+    // The bits past the end of each row's last word.
+    let unused = if width.is_multiple_of(64) {
+        0
+    } else {
+        !0 << (width % 64)
+    };
+    let births: Vec<u8> = (0..=8).filter(|&n| rule.born(n)).collect();
+    let survivals: Vec<u8> = (0..=8).filter(|&n| rule.survives(n)).collect();
+
+    let row_at = |row: usize| &alive[row * words..(row + 1) * words];
+    for row in 0..height {
+        let above = if row > 0 {
+            Some(row - 1)
+        } else {
+            wrap.then_some(height - 1)
+        };
+        let below = if row + 1 < height {
+            Some(row + 1)
+        } else {
+            wrap.then_some(0)
+        };
+        let cells_row = row_at(row);
+        for word in 0..words {
+            let mut count = Count::default();
+            for neighbors in [above, below].into_iter().flatten() {
+                let neighbors = row_at(neighbors);
+                add(&mut count, west(neighbors, word, width, wrap));
+                add(&mut count, neighbors[word]);
+                add(&mut count, east(neighbors, word, width, wrap));
+            }
+            add(&mut count, west(cells_row, word, width, wrap));
+            add(&mut count, east(cells_row, word, width, wrap));
+
+            let mut dead = !cells_row[word];
+            if multi_state {
+                dead &= !dying[row * words + word];
+            }
+            if word == words - 1 {
+                dead &= !unused;
+            }
+            let born = dead & any_of(&count, &births);
+            let survive = cells_row[word] & any_of(&count, &survivals);
+            let live = born | survive;
+            let start = row * width + word * 64;
+            let end = (start + 64).min((row + 1) * width);
+            if multi_state {
+                for (bit, idx) in (start..end).enumerate() {
+                    next[idx] = if live >> bit & 1 == 1 {
+                        Rule::ALIVE
+                    } else {
+                        rule.fade(cells[idx])
+                    };
+                }
+            } else {
+                for (bit, cell) in next[start..end].iter_mut().enumerate() {
+                    *cell = (live >> bit & 1) as u8 * Rule::ALIVE;
+                }
+            }
+        }
+    }
+}
+
+/// Up to 64 cells as the bits of a word, set where `set` holds.
+fn pack(cells: &[u8], set: impl Fn(u8) -> bool) -> u64 {
+    cells
+        .iter()
+        .enumerate()
+        .fold(0, |word, (bit, &cell)| word | u64::from(set(cell)) << bit)
+}
+
+/// Adds a bit to each of 64 counts, rippling the carries up through the
+/// slices.
+fn add(count: &mut Count, mut carry: u64) {
+    for slice in count {
+        let overflow = *slice & carry;
+        *slice ^= carry;
+        carry = overflow;
+    }
+}
+
+/// The cells whose count is one of `counts`.
+fn any_of(count: &Count, counts: &[u8]) -> u64 {
+    counts.iter().fold(0, |cells, &n| {
+        let equal = count.iter().enumerate().fold(!0, |equal, (bit, &slice)| {
+            equal & if n >> bit & 1 == 1 { slice } else { !slice }
+        });
+        cells | equal
+    })
+}
+
+/// Word `word` of the row moved a column east, so each cell holds its
+/// western neighbour.
+fn west(row: &[u64], word: usize, width: usize, wrap: bool) -> u64 {
+    let carry = match word {
+        0 => (wrap && row[(width - 1) / 64] >> ((width - 1) % 64) & 1 == 1) as u64,
+        _ => row[word - 1] >> 63,
+    };
+    let shifted = row[word] << 1 | carry;
+    // The last cell has moved past the end of the row.
+    if word == (width - 1) / 64 && !width.is_multiple_of(64) {
+        shifted & ((1 << (width % 64)) - 1)
+    } else {
+        shifted
+    }
+}
+
+/// Word `word` of the row moved a column west, so each cell holds its
+/// eastern neighbour.
+fn east(row: &[u64], word: usize, width: usize, wrap: bool) -> u64 {
+    let shifted = row[word] >> 1 | row.get(word + 1).map_or(0, |next| next << 63);
+    if wrap && word == (width - 1) / 64 {
+        shifted | (row[0] & 1) << ((width - 1) % 64)
+    } else {
+        shifted
+    }
+}
+
+#[cfg(test)]
+#[path = "swar_test.rs"]
+mod swar_test;
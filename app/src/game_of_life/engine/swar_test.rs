@@ -0,0 +1,72 @@
+use super::*;
+
+fn row(columns: &[usize], width: usize) -> Vec<u64> {
+    let mut words = vec![0; width.div_ceil(64)];
+    for &column in columns {
+        words[column / 64] |= 1 << (column % 64);
+    }
+    words
+}
+
+/// Every word of `cells`, a row `width` wide, shifted.
+fn shifted(
+    cells: &[u64],
+    width: usize,
+    shift: fn(&[u64], usize, usize, bool) -> u64,
+    wrap: bool,
+) -> Vec<u64> {
+    (0..cells.len())
+        .map(|word| shift(cells, word, width, wrap))
+        .collect()
+}
+
+#[test]
+fn test_shifts_carry_across_words() {
+    let width = 70;
+    let cells = row(&[0, 63, 69], width);
+    assert_eq!(shifted(&cells, width, west, false), row(&[1, 64], width));
+    assert_eq!(shifted(&cells, width, west, true), row(&[0, 1, 64], width));
+    assert_eq!(shifted(&cells, width, east, false), row(&[62, 68], width));
+    assert_eq!(
+        shifted(&cells, width, east, true),
+        row(&[62, 68, 69], width)
+    );
+}
+
+#[test]
+fn test_bit_sliced_counts() {
+    let mut count = Count::default();
+    // Cell n gets n added to its count.
+    for bit in 0..8 {
+        add(&mut count, (0xFF << (bit + 1)) & 0x1FF);
+    }
+    // Cells 9 and up were never added to, so only count 0 matches them.
+    for n in 0..=8u8 {
+        assert_eq!(any_of(&count, &[n]) & 0x1FF, 1 << n);
+    }
+    assert_eq!(any_of(&count, &[0]), !0x1FF | 1);
+    assert_eq!(any_of(&count, &[2, 3]), 0b1100);
+    assert_eq!(any_of(&count, &[]), 0);
+}
+
+#[test]
+fn test_blinker_on_a_dead_border() {
+    // A vertical blinker against the left edge, with half its arms cut off.
+    let (width, height) = (3, 3);
+    let cells = [0, 1, 0, 0, 1, 0, 0, 1, 0];
+    let mut next = [9; 9];
+    step(&cells, &mut next, width, height, Rule::CONWAY, false);
+    assert_eq!(next, [0, 0, 0, 1, 1, 1, 0, 0, 0]);
+}
+
+#[test]
+fn test_dying_cells_are_not_born() {
+    // Brian's Brain: cells are born with two live neighbours, never survive,
+    // and spend a generation dying.
+    let rule: Rule = "B2/S/C3".parse().unwrap();
+    let (width, height) = (3, 1);
+    let cells = [1, 2, 1];
+    let mut next = [9; 3];
+    step(&cells, &mut next, width, height, rule, false);
+    assert_eq!(next, [2, 0, 2]);
+}
@@ -0,0 +1,690 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::str::FromStr;
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+
+use super::hashlife::HashLife;
+use super::pattern::Pattern;
+use super::rule::Rule;
+use super::soup::Soup;
+use super::swar;
+
+/// A rectangle of cells, such as the user's selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Selection {
+    pub row: i64,
+    pub column: i64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Selection {
+    /// The rectangle spanning two opposite corner cells, inclusive.
+    pub fn from_corners(a: (i64, i64), b: (i64, i64)) -> Selection {
+        Selection {
+            row: a.0.min(b.0),
+            column: a.1.min(b.1),
+            width: a.1.abs_diff(b.1) as u32 + 1,
+            height: a.0.abs_diff(b.0) as u32 + 1,
+        }
+    }
+
+    /// The smallest rectangle holding every one of `cells`, if there are any.
+    pub fn around(mut cells: impl Iterator<Item = (i64, i64)>) -> Option<Selection> {
+        let first = cells.next()?;
+        let (top_left, bottom_right) = cells.fold((first, first), |(a, b), (row, column)| {
+            (
+                (a.0.min(row), a.1.min(column)),
+                (b.0.max(row), b.1.max(column)),
+            )
+        });
+        Some(Selection::from_corners(top_left, bottom_right))
+    }
+}
+
+/// A Game of Life simulation the page can drive and draw. Coordinates are
+/// signed so unbounded backends can grow in every direction; bounded ones wrap
+/// them onto their grid.
+pub trait UniverseBackend {
+    fn rule(&self) -> Rule;
+    fn set_rule(&mut self, rule: Rule);
+    fn state(&self, row: i64, column: i64) -> u8;
+    fn set_state(&mut self, row: i64, column: i64, state: u8);
+    fn tick(&mut self);
+
+    /// How many cells are alive, not counting those dying off under a
+    /// Generations rule.
+    fn population(&self) -> u64;
+
+    /// The period of the cycle the universe has settled into: 1 for a still
+    /// life, 2 for a blinker. `None` until a recent generation repeats, or if
+    /// the backend doesn't look for cycles.
+    fn period(&self) -> Option<u32> {
+        None
+    }
+
+    /// The universe as its own type, for backends outside the engine that
+    /// callers need to recognise.
+    fn as_any(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    /// Advances 2^k generations at once.
+    fn step_pow2(&mut self, k: u32) {
+        for _ in 0..1u64 << k {
+            self.tick();
+        }
+    }
+
+    /// Advances `generations` generations, in as few jumps as `step_pow2`
+    /// allows.
+    fn run(&mut self, generations: u64) {
+        for k in 0..u64::BITS {
+            if generations >> k & 1 == 1 {
+                self.step_pow2(k);
+            }
+        }
+    }
+
+    /// The smallest rectangle holding every cell that isn't dead, or `None`
+    /// if they all are or the backend can't tell.
+    fn bounds(&self) -> Option<Selection> {
+        None
+    }
+
+    /// Fills the universe with the cells of `soup`.
+    fn reset(&mut self, soup: Soup);
+    fn kill_all(&mut self);
+
+    fn toggle_cell(&mut self, row: i64, column: i64) {
+        log::info!("Toggling Cell at: ({}, {})", row, column);
+        let state = if self.state(row, column) == Rule::DEAD {
+            Rule::ALIVE
+        } else {
+            Rule::DEAD
+        };
+        self.set_state(row, column, state);
+    }
+
+    /// Stamps `pattern` with its top left corner at the given cell. States the
+    /// rule doesn't have become live cells.
+    fn insert_pattern(&mut self, pattern: &Pattern, row: i64, column: i64) {
+        log::info!(
+            "Inserting {}x{} pattern at: ({}, {})",
+            pattern.width,
+            pattern.height,
+            row,
+            column
+        );
+        let states = self.rule().states();
+        for pattern_row in 0..pattern.height {
+            for pattern_col in 0..pattern.width {
+                let state = match pattern.get(pattern_row, pattern_col) {
+                    state if state < states => state,
+                    _ => Rule::ALIVE,
+                };
+                self.set_state(row + pattern_row as i64, column + pattern_col as i64, state);
+            }
+        }
+    }
+
+    /// The cells in `selection`.
+    fn to_pattern(&self, selection: Selection) -> Pattern {
+        let mut pattern = Pattern::new(selection.width, selection.height);
+        for row in 0..selection.height {
+            for col in 0..selection.width {
+                pattern.cells[(row * selection.width + col) as usize] =
+                    self.state(selection.row + row as i64, selection.column + col as i64);
+            }
+        }
+        pattern.rule = Some(self.rule());
+        pattern
+    }
+
+    /// Kills every cell in `selection`.
+    fn clear(&mut self, selection: Selection) {
+        for row in 0..selection.height as i64 {
+            for column in 0..selection.width as i64 {
+                self.set_state(selection.row + row, selection.column + column, Rule::DEAD);
+            }
+        }
+    }
+
+    fn insert_pulsar(&mut self, row: i64, column: i64) {
+        log::info!("Inserting Pulsar at: ({}, {})", row, column);
+        for (delta_row, delta_col, value) in [
+            (-4, 1, true),
+            (-3, 1, true),
+            (-2, 1, true),
+            (-1, 1, false),
+            (0, 1, false),
+            (1, 1, false),
+            (2, 1, true),
+            (3, 1, true),
+            (4, 1, true),
+            (-4, 0, true),
+            (-3, 0, false),
+            (-2, 0, true),
+            (-1, 0, false),
+            (0, 0, false),
+            (1, 0, false),
+            (2, 0, true),
+            (3, 0, false),
+            (4, 0, true),
+            (-4, -1, true),
+            (-3, -1, true),
+            (-2, -1, true),
+            (-1, -1, false),
+            (0, -1, false),
+            (1, -1, false),
+            (2, -1, true),
+            (3, -1, true),
+            (4, -1, true),
+        ] {
+            self.set_state(row + delta_row, column + delta_col, value as u8);
+        }
+    }
+
+    fn insert_glider(&mut self, row: i64, column: i64) {
+        log::info!("Inserting Glider at: ({}, {})", row, column);
+        for (delta_row, delta_col, value) in [
+            (-1, 1, true),
+            (0, 1, true),
+            (1, 1, true),
+            (-1, 0, true),
+            (0, 0, false),
+            (1, 0, false),
+            (-1, -1, false),
+            (0, -1, true),
+            (1, -1, false),
+        ] {
+            self.set_state(row + delta_row, column + delta_col, value as u8);
+        }
+    }
+}
+
+/// How a `Universe` treats the cells beyond its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, EnumString, strum::Display)]
+pub enum Topology {
+    /// Opposite edges are joined.
+    #[default]
+    Torus,
+    /// Everything beyond the edges is dead.
+    #[strum(serialize = "Dead Border")]
+    Bounded,
+    /// Left and right are joined, while leaving through the top or bottom
+    /// comes back mirrored.
+    #[strum(serialize = "Klein Bottle")]
+    KleinBottle,
+    /// The grid grows whenever something reaches an edge.
+    #[strum(serialize = "Growing")]
+    Infinite,
+}
+
+/// The simulations the page can switch between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `Universe`, a fixed size grid.
+    Grid(Topology),
+    /// `HashLife`, an unbounded plane.
+    HashLife,
+    /// A torus simulated in a fragment shader.
+    Gpu,
+}
+
+impl Default for Backend {
+    fn default() -> Backend {
+        Backend::Grid(Topology::default())
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Backend::Grid(topology) => write!(f, "{}", topology),
+            Backend::HashLife => write!(f, "Hashlife"),
+            Backend::Gpu => write!(f, "GPU"),
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Backend, Self::Err> {
+        match s {
+            "Hashlife" => Ok(Backend::HashLife),
+            "GPU" => Ok(Backend::Gpu),
+            _ => s.parse().map(Backend::Grid),
+        }
+    }
+}
+
+impl Backend {
+    pub fn all() -> impl Iterator<Item = Backend> {
+        Topology::iter()
+            .map(Backend::Grid)
+            .chain([Backend::HashLife, Backend::Gpu])
+    }
+
+    /// A universe of this backend filled with `soup`. The GPU needs a WebGL
+    /// context, so here it runs on the CPU as a torus.
+    pub fn create(
+        self,
+        width: u32,
+        height: u32,
+        rule: Rule,
+        soup: Soup,
+    ) -> Box<dyn UniverseBackend> {
+        match self {
+            Backend::Grid(topology) => {
+                Box::new(Universe::new(width, height, rule, soup).with_topology(topology))
+            }
+            Backend::HashLife => Box::new(HashLife::new(width, height, rule, soup)),
+            Backend::Gpu => Box::new(Universe::new(width, height, rule, soup)),
+        }
+    }
+
+    /// The largest k a tick may jump 2^k generations by. Grids tick one
+    /// generation at a time, so it is kept small.
+    pub fn max_jump(self) -> u32 {
+        match self {
+            Backend::Grid(_) | Backend::Gpu => 6,
+            Backend::HashLife => 32,
+        }
+    }
+
+    pub fn supports(self, rule: Rule) -> bool {
+        match self {
+            Backend::Grid(_) | Backend::Gpu => true,
+            Backend::HashLife => HashLife::supports(rule),
+        }
+    }
+}
+
+/// A grid of cells, each holding a state of its `Rule`.
+#[derive(Default)]
+pub struct Universe {
+    width: u32,
+    height: u32,
+    cells: Vec<u8>,
+    _next: Vec<u8>,
+    rule: Rule,
+    topology: Topology,
+    /// The cell the top left of the grid holds; only a growing grid moves it.
+    top: i64,
+    left: i64,
+    /// The size the grid was created with, which a growing grid shrinks
+    /// back to when cleared.
+    home: (u32, u32),
+    /// Hashes of the last few generations, newest last. Editing the cells
+    /// forgets them.
+    recent: VecDeque<u64>,
+    period: Option<u32>,
+}
+
+impl Universe {
+    /// Cells a growing grid adds to a side when something reaches it.
+    const GROWTH: u32 = 16;
+    /// A growing grid stops growing at this many cells a side.
+    const MAX_SIDE: u32 = 4096;
+    /// How many generations are remembered, and so the longest period found.
+    const MEMORY: usize = 64;
+
+    pub fn new(width: u32, height: u32, rule: Rule, soup: Soup) -> Universe {
+        Universe {
+            width,
+            height,
+            cells: soup.cells((width * height) as usize),
+            _next: vec![Rule::DEAD; (width * height) as usize],
+            rule,
+            topology: Topology::default(),
+            top: 0,
+            left: 0,
+            home: (width, height),
+            recent: VecDeque::new(),
+            period: None,
+        }
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Universe {
+        self.topology = topology;
+        self
+    }
+}
+
+impl UniverseBackend for Universe {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Cells in states the new rule doesn't have are killed.
+    fn set_rule(&mut self, rule: Rule) {
+        log::info!("Setting rule to {}", rule);
+        self.rule = rule;
+        for cell in self.cells.iter_mut().filter(|cell| **cell >= rule.states()) {
+            *cell = Rule::DEAD;
+        }
+        self.forget();
+    }
+
+    fn state(&self, row: i64, column: i64) -> u8 {
+        self.locate(row, column)
+            .map_or(Rule::DEAD, |idx| self.cells[idx])
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        if self.topology == Topology::Infinite && state != Rule::DEAD {
+            self.reach(row, column);
+        }
+        if let Some(idx) = self.locate(row, column) {
+            if self.cells[idx] != state {
+                self.cells[idx] = state;
+                self.forget();
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.recent.is_empty() {
+            self.remember();
+        }
+        if self.topology == Topology::Infinite {
+            self.grow();
+        }
+        match self.topology {
+            // Mirroring rows doesn't fit shifting whole words about.
+            Topology::KleinBottle => self.step_cells(),
+            topology => swar::step(
+                &self.cells,
+                &mut self._next,
+                self.width,
+                self.height,
+                self.rule,
+                topology == Topology::Torus,
+            ),
+        }
+        std::mem::swap(&mut self.cells, &mut self._next);
+        self.remember();
+    }
+
+    fn population(&self) -> u64 {
+        self.cells
+            .iter()
+            .filter(|&&cell| cell == Rule::ALIVE)
+            .count() as u64
+    }
+
+    fn period(&self) -> Option<u32> {
+        self.period
+    }
+
+    fn bounds(&self) -> Option<Selection> {
+        let width = self.width as usize;
+        Selection::around(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell != Rule::DEAD)
+                .map(|(idx, _)| {
+                    (
+                        self.top + (idx / width) as i64,
+                        self.left + (idx % width) as i64,
+                    )
+                }),
+        )
+    }
+
+    fn reset(&mut self, soup: Soup) {
+        log::info!("Resetting Universe");
+        self.shrink();
+        self.cells = soup.cells(self.cells.len());
+        self.forget();
+    }
+
+    fn kill_all(&mut self) {
+        log::info!("Clearing Universe");
+        self.shrink();
+        self.cells.fill(Rule::DEAD);
+        self.forget();
+    }
+}
+
+impl Universe {
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+
+    /// Where a cell is stored, following the topology across the edges.
+    /// Cells beyond a bounded or growing grid have nowhere to go.
+    fn locate(&self, row: i64, column: i64) -> Option<usize> {
+        let (height, width) = (self.height as i64, self.width as i64);
+        let (row, column) = (row - self.top, column - self.left);
+        let (row, column) = match self.topology {
+            Topology::Torus => (row.rem_euclid(height), column.rem_euclid(width)),
+            Topology::KleinBottle => {
+                let column = if row.div_euclid(height) % 2 == 0 {
+                    column
+                } else {
+                    width - 1 - column
+                };
+                (row.rem_euclid(height), column.rem_euclid(width))
+            }
+            Topology::Bounded | Topology::Infinite => {
+                if !(0..height).contains(&row) || !(0..width).contains(&column) {
+                    return None;
+                }
+                (row, column)
+            }
+        };
+        Some(self.get_index(row as u32, column as u32))
+    }
+
+    /// Notes the generation the cells hold, and how far back it last came
+    /// up. Only hashes are kept, so a collision could report a false cycle,
+    /// though with 64 bits that is vanishingly unlikely.
+    fn remember(&mut self) {
+        let mut hasher = DefaultHasher::new();
+        (self.top, self.left, self.width, &self.cells).hash(&mut hasher);
+        let hash = hasher.finish();
+        self.period = self
+            .recent
+            .iter()
+            .rev()
+            .position(|&seen| seen == hash)
+            .map(|back| back as u32 + 1);
+        if self.recent.len() == Universe::MEMORY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(hash);
+    }
+
+    fn forget(&mut self) {
+        self.recent.clear();
+        self.period = None;
+    }
+
+    /// Grows the grid on every side something alive has reached, so births
+    /// just beyond it aren't lost.
+    fn grow(&mut self) {
+        let live = |row: u32, column: u32| self.cells[self.get_index(row, column)] != Rule::DEAD;
+        let (last_row, last_column) = (self.height - 1, self.width - 1);
+        let grow_top = (0..self.width).any(|column| live(0, column));
+        let grow_bottom = (0..self.width).any(|column| live(last_row, column));
+        let grow_left = (0..self.height).any(|row| live(row, 0));
+        let grow_right = (0..self.height).any(|row| live(row, last_column));
+        self.extend(
+            Universe::GROWTH * grow_top as u32,
+            Universe::GROWTH * grow_bottom as u32,
+            Universe::GROWTH * grow_left as u32,
+            Universe::GROWTH * grow_right as u32,
+        );
+    }
+
+    /// Grows the grid until it covers the cell, with some room around it.
+    fn reach(&mut self, row: i64, column: i64) {
+        let (row, column) = (row - self.top, column - self.left);
+        let margin = |below: i64| match below {
+            below if below < 0 => (-below) as u32 + Universe::GROWTH,
+            _ => 0,
+        };
+        self.extend(
+            margin(row),
+            margin(self.height as i64 - 1 - row),
+            margin(column),
+            margin(self.width as i64 - 1 - column),
+        );
+    }
+
+    /// Adds dead cells around the grid, short of `MAX_SIDE`.
+    fn extend(&mut self, top: u32, bottom: u32, left: u32, right: u32) {
+        let (top, bottom) = match self.height + top + bottom {
+            height if height > Universe::MAX_SIDE => (0, 0),
+            _ => (top, bottom),
+        };
+        let (left, right) = match self.width + left + right {
+            width if width > Universe::MAX_SIDE => (0, 0),
+            _ => (left, right),
+        };
+        if top + bottom + left + right > 0 {
+            self.reframe(
+                self.top - top as i64,
+                self.left - left as i64,
+                self.width + left + right,
+                self.height + top + bottom,
+            );
+        }
+    }
+
+    /// Returns a grown grid to the size it was created with.
+    fn shrink(&mut self) {
+        let (width, height) = self.home;
+        if (self.top, self.left, self.width, self.height) != (0, 0, width, height) {
+            self.reframe(0, 0, width, height);
+        }
+    }
+
+    /// Moves the grid to cover `width` x `height` cells from (`top`, `left`),
+    /// keeping the cells that are in both.
+    fn reframe(&mut self, top: i64, left: i64, width: u32, height: u32) {
+        let mut cells = vec![Rule::DEAD; (width * height) as usize];
+        for row in 0..height {
+            for column in 0..width {
+                let (old_row, old_column) = (
+                    top + row as i64 - self.top,
+                    left + column as i64 - self.left,
+                );
+                if (0..self.height as i64).contains(&old_row)
+                    && (0..self.width as i64).contains(&old_column)
+                {
+                    cells[(row * width + column) as usize] =
+                        self.cells[self.get_index(old_row as u32, old_column as u32)];
+                }
+            }
+        }
+        self.top = top;
+        self.left = left;
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self._next = vec![Rule::DEAD; (width * height) as usize];
+    }
+
+    /// Works out the next generation into `_next` a cell at a time, looking
+    /// each neighbour up through the topology.
+    fn step_cells(&mut self) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let cell = self.cells[idx];
+                let live_neighbors = self.live_neighbor_count(row, col);
+
+                self._next[idx] = self.rule.next(cell, live_neighbors);
+            }
+        }
+    }
+
+    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        // Away from the edges the neighbours are just the cells around, and
+        // only the border needs the topology to find them.
+        if (1..self.height - 1).contains(&row) && (1..self.width - 1).contains(&column) {
+            let width = self.width as usize;
+            let idx = self.get_index(row, column);
+            return [
+                idx - width - 1,
+                idx - width,
+                idx - width + 1,
+                idx - 1,
+                idx + 1,
+                idx + width - 1,
+                idx + width,
+                idx + width + 1,
+            ]
+            .into_iter()
+            .map(|idx| self.is_alive(idx) as u8)
+            .sum();
+        }
+        let (row, column) = (self.top + row as i64, self.left + column as i64);
+        let mut count = 0;
+        for delta_row in [-1, 0, 1] {
+            for delta_col in [-1, 0, 1] {
+                if (delta_row, delta_col) == (0, 0) {
+                    continue;
+                }
+                if let Some(idx) = self.locate(row + delta_row, column + delta_col) {
+                    count += self.is_alive(idx) as u8;
+                }
+            }
+        }
+        count
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell as an array.
+    #[allow(dead_code)]
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        self.cells.fill(Rule::DEAD);
+        for (row, col) in cells.iter().cloned() {
+            let idx = self.get_index(row, col);
+            self.cells[idx] = Rule::ALIVE;
+        }
+        self.forget();
+    }
+
+    #[allow(dead_code)]
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    #[allow(dead_code)]
+    pub fn cells(&self) -> *const u8 {
+        self.cells.as_ptr()
+    }
+
+    pub fn is_alive(&self, idx: usize) -> bool {
+        self.cells[idx] == Rule::ALIVE
+    }
+}
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks_exact(self.width.max(1) as usize) {
+            for &cell in row {
+                let symbol = match cell {
+                    Rule::DEAD => '◻',
+                    Rule::ALIVE => '◼',
+                    _ => '▣',
+                };
+                write!(f, "{}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "universe_test.rs"]
+mod universe_test;
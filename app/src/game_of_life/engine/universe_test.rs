@@ -0,0 +1,252 @@
+use super::*;
+use crate::game_of_life::engine::library;
+extern crate test;
+use test::Bencher;
+
+fn blank(width: u32, height: u32, topology: Topology) -> Universe {
+    Universe::new(width, height, Rule::CONWAY, Soup::new(0, 0.0)).with_topology(topology)
+}
+
+fn horizontal_blinker(universe: &mut Universe) {
+    for column in 3..6 {
+        universe.set_state(0, column, Rule::ALIVE);
+    }
+}
+
+#[test]
+fn test_torus_wraps_blinker() {
+    let mut universe = blank(8, 8, Topology::Torus);
+    horizontal_blinker(&mut universe);
+    universe.tick();
+    assert_eq!(universe.state(-1, 4), Rule::ALIVE);
+    assert_eq!(universe.state(7, 4), Rule::ALIVE);
+    universe.tick();
+    assert_eq!(universe.population(), 3);
+}
+
+#[test]
+fn test_dead_border_kills_blinker() {
+    let mut universe = blank(8, 8, Topology::Bounded);
+    horizontal_blinker(&mut universe);
+    assert_eq!(universe.state(-1, 4), Rule::DEAD);
+    universe.set_state(-1, 4, Rule::ALIVE);
+    assert_eq!(universe.population(), 3);
+    universe.tick();
+    assert_eq!(universe.population(), 2);
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+}
+
+#[test]
+fn test_klein_bottle_mirrors_across_top_and_bottom() {
+    let mut universe = blank(8, 6, Topology::KleinBottle);
+    universe.set_state(-1, 1, Rule::ALIVE);
+    assert_eq!(universe.state(5, 6), Rule::ALIVE);
+    universe.set_state(2, -1, Rule::ALIVE);
+    assert_eq!(universe.state(2, 7), Rule::ALIVE);
+    assert_eq!(universe.state(8, 0), Rule::ALIVE);
+}
+
+#[test]
+fn test_klein_bottle_glider_crosses_the_seam() {
+    // The glider runs down and right through the interior and on across the
+    // mirrored seam, keeping its shape.
+    let mut universe = blank(10, 10, Topology::KleinBottle);
+    let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    universe.insert_pattern(&glider, 3, 3);
+    for _ in 0..4 * 5 {
+        universe.tick();
+    }
+    assert_eq!(universe.population(), 5);
+    let selection = Selection {
+        row: 8,
+        column: 8,
+        width: 3,
+        height: 3,
+    };
+    assert_eq!(universe.to_pattern(selection).cells, glider.cells);
+    for _ in 0..4 * 2 {
+        universe.tick();
+    }
+    assert_eq!(universe.population(), 5);
+}
+
+#[test]
+fn test_growing_grid_follows_glider() {
+    let mut universe = blank(8, 8, Topology::Infinite);
+    let glider = Pattern::from_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    universe.insert_pattern(&glider, 2, 2);
+    for _ in 0..4 * 40 {
+        universe.tick();
+    }
+    assert_eq!(universe.population(), 5);
+    let selection = Selection {
+        row: 42,
+        column: 42,
+        width: 3,
+        height: 3,
+    };
+    assert_eq!(universe.to_pattern(selection).cells, glider.cells);
+
+    universe.kill_all();
+    assert_eq!((universe.width, universe.height), (8, 8));
+}
+
+#[test]
+fn test_growing_grid_reaches_edited_cells() {
+    let mut universe = blank(8, 8, Topology::Infinite);
+    universe.set_state(-20, 30, Rule::ALIVE);
+    assert_eq!(universe.state(-20, 30), Rule::ALIVE);
+    assert_eq!(universe.state(0, 0), Rule::DEAD);
+    universe.set_state(500, 500, Rule::DEAD);
+    assert_eq!(universe.width, 8 + 23 + Universe::GROWTH);
+}
+
+#[test]
+fn test_still_life_has_period_one() {
+    let mut universe = blank(8, 8, Topology::Torus);
+    for (row, column) in [(2, 2), (2, 3), (3, 2), (3, 3)] {
+        universe.set_state(row, column, Rule::ALIVE);
+    }
+    assert_eq!(universe.period(), None);
+    universe.tick();
+    assert_eq!(universe.period(), Some(1));
+}
+
+#[test]
+fn test_oscillator_periods() {
+    let mut universe = blank(8, 8, Topology::Torus);
+    horizontal_blinker(&mut universe);
+    universe.tick();
+    assert_eq!(universe.period(), None);
+    universe.tick();
+    assert_eq!(universe.period(), Some(2));
+
+    let mut universe = blank(32, 32, Topology::Torus);
+    universe.insert_pattern(&library::load("Pulsar").unwrap(), 8, 8);
+    universe.step_pow2(1);
+    assert_eq!(universe.period(), None);
+    universe.tick();
+    assert_eq!(universe.period(), Some(3));
+}
+
+#[test]
+fn test_edits_forget_the_cycle() {
+    let mut universe = blank(8, 8, Topology::Torus);
+    horizontal_blinker(&mut universe);
+    universe.step_pow2(2);
+    assert_eq!(universe.period(), Some(2));
+    // Setting a cell to what it holds changes nothing.
+    universe.set_state(0, 4, Rule::ALIVE);
+    assert_eq!(universe.period(), Some(2));
+    universe.toggle_cell(5, 1);
+    assert_eq!(universe.period(), None);
+    universe.tick();
+    assert_eq!(universe.period(), None);
+}
+
+#[test]
+fn test_glider_never_repeats_on_a_growing_grid() {
+    let mut universe = blank(16, 16, Topology::Infinite);
+    universe.insert_glider(4, 4);
+    universe.step_pow2(6);
+    assert_eq!(universe.period(), None);
+}
+
+#[test]
+fn test_backend_names_round_trip() {
+    for backend in Backend::all() {
+        assert_eq!(backend.to_string().parse::<Backend>(), Ok(backend));
+    }
+    assert_eq!(
+        "Klein Bottle".parse(),
+        Ok(Backend::Grid(Topology::KleinBottle))
+    );
+    assert!("Sphere".parse::<Backend>().is_err());
+}
+
+/// Two copies of the same random soup.
+fn soups(width: u32, height: u32, topology: Topology, rule: Rule) -> (Universe, Universe) {
+    let soup = Universe::new(width, height, rule, Soup::random()).with_topology(topology);
+    let mut copy = blank(width, height, topology);
+    copy.set_rule(rule);
+    copy.insert_pattern(
+        &soup.to_pattern(Selection::from_corners(
+            (0, 0),
+            (height as i64 - 1, width as i64 - 1),
+        )),
+        0,
+        0,
+    );
+    (soup, copy)
+}
+
+#[test]
+fn test_word_at_a_time_matches_cell_at_a_time() {
+    let rules = ["B3/S23", "B36/S23", "B0/S8", "B2/S/C3", "B34/S12/C3"];
+    let sizes = [(1, 1), (3, 2), (64, 64), (65, 7), (130, 33)];
+    for rule in rules.map(|rule| rule.parse::<Rule>().unwrap()) {
+        for (width, height) in sizes {
+            for topology in [Topology::Torus, Topology::Bounded] {
+                let (mut words, mut cells) = soups(width, height, topology, rule);
+                for _ in 0..8 {
+                    words.tick();
+                    cells.step_cells();
+                    std::mem::swap(&mut cells.cells, &mut cells._next);
+                    assert_eq!(
+                        words.cells, cells.cells,
+                        "{rule} on a {width}x{height} {topology}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[bench]
+fn bench_tick_word_at_a_time(b: &mut Bencher) {
+    let mut universe = Universe::new(256, 256, Rule::CONWAY, Soup::random());
+    b.iter(|| {
+        swar::step(
+            &universe.cells,
+            &mut universe._next,
+            256,
+            256,
+            Rule::CONWAY,
+            true,
+        );
+        std::mem::swap(&mut universe.cells, &mut universe._next);
+    });
+}
+
+const NEIGHBORS: [(i64, i64); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Ticks a cell at a time, looking every neighbour up through the topology.
+#[bench]
+fn bench_tick_cell_at_a_time(b: &mut Bencher) {
+    let mut universe = Universe::new(256, 256, Rule::CONWAY, Soup::random());
+    b.iter(|| {
+        for row in 0..universe.height as i64 {
+            for column in 0..universe.width as i64 {
+                let mut count = 0;
+                for (delta_row, delta_col) in NEIGHBORS {
+                    if let Some(idx) = universe.locate(row + delta_row, column + delta_col) {
+                        count += universe.is_alive(idx) as u8;
+                    }
+                }
+                let idx = universe.get_index(row as u32, column as u32);
+                universe._next[idx] = universe.rule.next(universe.cells[idx], count);
+            }
+        }
+        std::mem::swap(&mut universe.cells, &mut universe._next);
+    });
+}
@@ -0,0 +1,87 @@
+use leptos::prelude::*;
+
+use super::engine::rule::Rule;
+use super::engine::universe::Backend;
+
+/// Smallest and largest grid sides the size boxes accept.
+const MIN_SIDE: u32 = 8;
+const MAX_SIDE: u32 = 1024;
+
+/// Chooses the simulation backend, the grid size and how many generations,
+/// as a power of two, each tick advances.
+#[component]
+pub fn EnginePicker(
+    #[prop(into)] backend: RwSignal<Backend>,
+    #[prop(into)] jump: RwSignal<u32>,
+    #[prop(into)] size: RwSignal<(u32, u32)>,
+    #[prop(into)] rule: Signal<Rule>,
+) -> impl IntoView {
+    let hashlife_supported = move || Backend::HashLife.supports(rule.get());
+
+    let set_side = move |value: String, set: fn(&mut (u32, u32), u32)| {
+        if let Ok(side) = value.parse::<u32>() {
+            size.update(|size| set(size, side.clamp(MIN_SIDE, MAX_SIDE)));
+        }
+    };
+
+    view! {
+        <div class="life-engine">
+            <select
+                class="engine-select"
+                prop:value=move || backend.get().to_string()
+                on:change=move |ev| {
+                    if let Ok(picked) = event_target_value(&ev).parse::<Backend>() {
+                        backend.set(picked);
+                        jump.update(|k| *k = (*k).min(picked.max_jump()));
+                    }
+                }
+            >
+                {Backend::all().map(|option| {
+                    view! {
+                        <option
+                            value=option.to_string()
+                            disabled=move || !option.supports(rule.get())
+                        >
+                            {option.to_string()}
+                        </option>
+                    }
+                }).collect_view()}
+            </select>
+            <label class="engine-size">
+                "Size: "
+                <input
+                    type="number"
+                    min=MIN_SIDE
+                    max=MAX_SIDE
+                    prop:value=move || size.get().0
+                    on:change=move |ev| set_side(event_target_value(&ev), |size, width| size.0 = width)
+                />
+                " x "
+                <input
+                    type="number"
+                    min=MIN_SIDE
+                    max=MAX_SIDE
+                    prop:value=move || size.get().1
+                    on:change=move |ev| set_side(event_target_value(&ev), |size, height| size.1 = height)
+                />
+            </label>
+            <label class="engine-jump">
+                "Generations per Tick: 2^"
+                <input
+                    type="number"
+                    min="0"
+                    max=move || backend.get().max_jump()
+                    prop:value=move || jump.get()
+                    on:change=move |ev| {
+                        if let Ok(k) = event_target_value(&ev).parse::<u32>() {
+                            jump.set(k.min(backend.get_untracked().max_jump()));
+                        }
+                    }
+                />
+            </label>
+            <Show when=move || !hashlife_supported()>
+                <span class="engine-note">"Hashlife needs a two-state rule without B0"</span>
+            </Show>
+        </div>
+    }
+}
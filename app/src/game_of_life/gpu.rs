@@ -0,0 +1,385 @@
+use std::any::Any;
+use std::cell::{Cell, Ref, RefCell};
+
+use web_sys::{
+    WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlRenderingContext as GL, WebGlTexture,
+};
+
+use super::engine::pattern::Pattern;
+use super::engine::rule::Rule;
+use super::engine::soup::Soup;
+use super::engine::universe::{Backend, Universe, UniverseBackend};
+use super::webgl::{compile_shader, link_program};
+
+/// A torus simulated by a fragment shader. The generation lives in one of
+/// two textures, and each tick draws the next into the other before they
+/// swap, so cells only cross to the GPU when edited and come back when
+/// something needs all of them.
+pub struct GpuLife {
+    gl: GL,
+    program: WebGlProgram,
+    buffer: WebGlBuffer,
+    /// The current generation and the one being drawn, with a framebuffer
+    /// drawing into each.
+    textures: [WebGlTexture; 2],
+    framebuffers: [WebGlFramebuffer; 2],
+    front: usize,
+    /// The rule as a lookup table, see `rule_table`.
+    table: WebGlTexture,
+    width: u32,
+    height: u32,
+    rule: Rule,
+    /// The cells as last read back or loaded, a state a cell.
+    cells: RefCell<Vec<u8>>,
+    /// Whether the GPU has run on since `cells` was filled, so it must be
+    /// read back before it is used.
+    stale: Cell<bool>,
+}
+
+/// A universe of `backend` filled with `soup`. The GPU backend simulates in
+/// `gl`, the context the canvas is drawn with, and runs on the CPU as a torus
+/// when there is none or it can't be set up.
+pub fn create(
+    backend: Backend,
+    width: u32,
+    height: u32,
+    rule: Rule,
+    soup: Soup,
+    gl: Option<&GL>,
+) -> Box<dyn UniverseBackend> {
+    if backend != Backend::Gpu {
+        return backend.create(width, height, rule, soup);
+    }
+    match gl.map(|gl| GpuLife::new(gl.clone(), width, height, rule, soup)) {
+        Some(Ok(life)) => Box::new(life),
+        failed => {
+            if let Some(Err(error)) = failed {
+                log::warn!("Simulating on the CPU: {}", error);
+            }
+            Box::new(Universe::new(width, height, rule, soup))
+        }
+    }
+}
+
+/// The next state for every state and count of live neighbours, a row per
+/// state with nine counts across.
+pub fn rule_table(rule: Rule) -> Vec<u8> {
+    (0..rule.states())
+        .flat_map(|state| (0..9).map(move |neighbors| rule.next(state, neighbors)))
+        .collect()
+}
+
+/// Texels holding each state in their red channel.
+pub fn to_texels(states: &[u8]) -> Vec<u8> {
+    states
+        .iter()
+        .flat_map(|&state| [state, 0, 0, 255])
+        .collect()
+}
+
+impl GpuLife {
+    /// `universe` as a GPU simulation, which renderers can draw without
+    /// reading the cells back.
+    pub fn of(universe: &dyn UniverseBackend) -> Option<&GpuLife> {
+        universe.as_any()?.downcast_ref()
+    }
+
+    /// A torus filled with `soup` simulated on the GPU behind `gl`, which is
+    /// shared with the renderer so it can draw straight from the simulation's
+    /// textures.
+    pub fn new(gl: GL, width: u32, height: u32, rule: Rule, soup: Soup) -> Result<GpuLife, String> {
+        let vert_shader = compile_shader(&gl, GL::VERTEX_SHADER, include_str!("./basic.vert"))?;
+        let frag_shader = compile_shader(&gl, GL::FRAGMENT_SHADER, include_str!("./life.frag"))?;
+        let program = link_program(&gl, &vert_shader, &frag_shader)?;
+
+        let texture = || {
+            let texture = gl
+                .create_texture()
+                .ok_or_else(|| String::from("Unable to create texture"))?;
+            gl.bind_texture(GL::TEXTURE_2D, Some(&texture));
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+            Ok::<_, String>(texture)
+        };
+        gl.active_texture(GL::TEXTURE0);
+        let textures = [texture()?, texture()?];
+        let mut framebuffers = Vec::new();
+        for texture in &textures {
+            gl.bind_texture(GL::TEXTURE_2D, Some(texture));
+            gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                GL::TEXTURE_2D,
+                0,
+                GL::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                GL::RGBA,
+                GL::UNSIGNED_BYTE,
+                None,
+            )
+            .map_err(|_| String::from("Unable to allocate the grid"))?;
+            let framebuffer = gl
+                .create_framebuffer()
+                .ok_or_else(|| String::from("Unable to create framebuffer"))?;
+            gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&framebuffer));
+            gl.framebuffer_texture_2d(
+                GL::FRAMEBUFFER,
+                GL::COLOR_ATTACHMENT0,
+                GL::TEXTURE_2D,
+                Some(texture),
+                0,
+            );
+            let complete = gl.check_framebuffer_status(GL::FRAMEBUFFER) == GL::FRAMEBUFFER_COMPLETE;
+            gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+            if !complete {
+                return Err(String::from("Unable to draw into the grid"));
+            }
+            framebuffers.push(framebuffer);
+        }
+        let table = texture()?;
+
+        let vertices: [f32; 12] = [
+            1.0, 1.0, 0.0, -1.0, 1.0, 0.0, 1.0, -1.0, 0.0, -1.0, -1.0, 0.0,
+        ];
+        let buffer = gl
+            .create_buffer()
+            .ok_or_else(|| String::from("Unable to create buffer"))?;
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+        // Safety: the view into WASM memory is used before anything else is
+        // allocated, so the memory can't grow and move under it.
+        unsafe {
+            let vert_array = js_sys::Float32Array::view(&vertices);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &vert_array, GL::STATIC_DRAW);
+        }
+
+        gl.use_program(Some(&program));
+        let size_loc = gl.get_uniform_location(&program, "size");
+        gl.uniform2f(size_loc.as_ref(), width as f32, height as f32);
+        let cells_loc = gl.get_uniform_location(&program, "cells");
+        gl.uniform1i(cells_loc.as_ref(), 0);
+        let table_loc = gl.get_uniform_location(&program, "table");
+        gl.uniform1i(table_loc.as_ref(), 1);
+
+        let framebuffers = [framebuffers.remove(0), framebuffers.remove(0)];
+        let mut life = GpuLife {
+            gl,
+            program,
+            buffer,
+            textures,
+            framebuffers,
+            front: 0,
+            table,
+            width,
+            height,
+            rule,
+            cells: RefCell::new(Vec::new()),
+            stale: Cell::new(true),
+        };
+        life.set_rule(rule);
+        life.reset(soup);
+        Ok(life)
+    }
+
+    /// The texture holding the current generation, with each cell's state in
+    /// its red channel.
+    pub fn texture(&self) -> &WebGlTexture {
+        &self.textures[self.front]
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Every cell, read back from the GPU if it has run on since last time.
+    fn cells(&self) -> Ref<'_, [u8]> {
+        if self.stale.get() {
+            let cells = self.read(0, 0, self.width, self.height);
+            self.cells.replace(cells);
+            self.stale.set(false);
+        }
+        Ref::map(self.cells.borrow(), Vec::as_slice)
+    }
+
+    /// The states of the `width` by `height` cells from (`x`, `y`) in the
+    /// current generation, row by row.
+    fn read(&self, x: u32, y: u32, width: u32, height: u32) -> Vec<u8> {
+        let mut pixels = vec![0; (width * height * 4) as usize];
+        self.gl
+            .bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffers[self.front]));
+        self.gl
+            .read_pixels_with_opt_u8_array(
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                GL::RGBA,
+                GL::UNSIGNED_BYTE,
+                Some(&mut pixels),
+            )
+            .ok();
+        self.gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        pixels.chunks_exact(4).map(|texel| texel[0]).collect()
+    }
+
+    /// Replaces every cell.
+    fn load(&mut self, cells: Vec<u8>) {
+        self.gl.active_texture(GL::TEXTURE0);
+        self.gl
+            .bind_texture(GL::TEXTURE_2D, Some(&self.textures[self.front]));
+        self.gl
+            .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+                GL::TEXTURE_2D,
+                0,
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                GL::RGBA,
+                GL::UNSIGNED_BYTE,
+                Some(&to_texels(&cells)),
+            )
+            .ok();
+        self.cells.replace(cells);
+        self.stale.set(false);
+    }
+
+    fn index(&self, row: i64, column: i64) -> usize {
+        let row = row.rem_euclid(self.height as i64) as usize;
+        let column = column.rem_euclid(self.width as i64) as usize;
+        row * self.width as usize + column
+    }
+}
+
+impl UniverseBackend for GpuLife {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Cells in states the new rule doesn't have are killed.
+    fn set_rule(&mut self, rule: Rule) {
+        log::info!("Setting rule to {}", rule);
+        let gl = &self.gl;
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.table));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D,
+            0,
+            GL::RGBA as i32,
+            9,
+            rule.states() as i32,
+            0,
+            GL::RGBA,
+            GL::UNSIGNED_BYTE,
+            Some(&to_texels(&rule_table(rule))),
+        )
+        .ok();
+        gl.use_program(Some(&self.program));
+        let states_loc = gl.get_uniform_location(&self.program, "states");
+        gl.uniform1f(states_loc.as_ref(), rule.states() as f32);
+        let fewer_states = rule.states() < self.rule.states();
+        self.rule = rule;
+        if fewer_states {
+            let mut cells = self.cells().to_vec();
+            for cell in cells.iter_mut().filter(|cell| **cell >= rule.states()) {
+                *cell = Rule::DEAD;
+            }
+            self.load(cells);
+        }
+    }
+
+    /// Reads back just the one cell if the copy is stale.
+    fn state(&self, row: i64, column: i64) -> u8 {
+        let idx = self.index(row, column);
+        if !self.stale.get() {
+            return self.cells.borrow()[idx];
+        }
+        let (x, y) = (idx % self.width as usize, idx / self.width as usize);
+        self.read(x as u32, y as u32, 1, 1)[0]
+    }
+
+    /// Uploads just the one cell, keeping the copy up to date if it isn't
+    /// stale.
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        let idx = self.index(row, column);
+        if !self.stale.get() {
+            self.cells.get_mut()[idx] = state;
+        }
+        let gl = &self.gl;
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.textures[self.front]));
+        gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D,
+            0,
+            (idx % self.width as usize) as i32,
+            (idx / self.width as usize) as i32,
+            1,
+            1,
+            GL::RGBA,
+            GL::UNSIGNED_BYTE,
+            Some(&to_texels(&[state])),
+        )
+        .ok();
+    }
+
+    fn tick(&mut self) {
+        let gl = &self.gl;
+        let back = 1 - self.front;
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffers[back]));
+        gl.viewport(0, 0, self.width as i32, self.height as i32);
+        gl.use_program(Some(&self.program));
+        gl.active_texture(GL::TEXTURE1);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.table));
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.textures[self.front]));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffer));
+        gl.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(0);
+        gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        self.front = back;
+        self.stale.set(true);
+    }
+
+    fn population(&self) -> u64 {
+        self.cells()
+            .iter()
+            .filter(|&&cell| cell == Rule::ALIVE)
+            .count() as u64
+    }
+
+    fn reset(&mut self, soup: Soup) {
+        log::info!("Resetting Universe");
+        self.load(soup.cells((self.width * self.height) as usize));
+    }
+
+    fn kill_all(&mut self) {
+        log::info!("Clearing Universe");
+        self.load(vec![Rule::DEAD; (self.width * self.height) as usize]);
+    }
+
+    /// Stamps the pattern in one upload rather than a cell at a time.
+    fn insert_pattern(&mut self, pattern: &Pattern, row: i64, column: i64) {
+        let states = self.rule.states();
+        let mut cells = self.cells().to_vec();
+        for pattern_row in 0..pattern.height {
+            for pattern_col in 0..pattern.width {
+                let state = match pattern.get(pattern_row, pattern_col) {
+                    state if state < states => state,
+                    _ => Rule::ALIVE,
+                };
+                cells[self.index(row + pattern_row as i64, column + pattern_col as i64)] = state;
+            }
+        }
+        self.load(cells);
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+#[path = "gpu_test.rs"]
+mod gpu_test;
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_rule_table_matches_the_rule() {
+    let table = rule_table(Rule::CONWAY);
+    assert_eq!(table.len(), 18);
+    assert_eq!(
+        table,
+        [
+            [0, 0, 0, 1, 0, 0, 0, 0, 0], // Births
+            [0, 0, 1, 1, 0, 0, 0, 0, 0], // Survivals
+        ]
+        .concat()
+    );
+
+    let brain: Rule = "B2/S/C3".parse().unwrap();
+    let table = rule_table(brain);
+    for (state, row) in table.chunks(9).enumerate() {
+        for (neighbors, &next) in row.iter().enumerate() {
+            assert_eq!(next, brain.next(state as u8, neighbors as u8));
+        }
+    }
+}
+
+#[test]
+fn test_states_go_in_the_red_channel() {
+    assert_eq!(
+        to_texels(&[0, 1, 2]),
+        [0, 0, 0, 255, 1, 0, 0, 255, 2, 0, 0, 255]
+    );
+}
@@ -0,0 +1,159 @@
+use super::engine::rule::Rule;
+use super::engine::soup::Soup;
+use super::engine::universe::{Selection, UniverseBackend};
+
+/// A cell an edit changed, and what it held before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Change {
+    row: i64,
+    column: i64,
+    before: u8,
+    after: u8,
+}
+
+/// Undo and redo for edits made by hand. Generations aren't edits: stepping
+/// the universe clears the history, as earlier edits have been evolved away.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    /// Whether edits are being gathered into the last undo step.
+    open: bool,
+}
+
+impl History {
+    /// Gathers the edits until `end` into a single undo step, such as every
+    /// cell a brush stroke touches.
+    pub fn begin(&mut self) {
+        self.end();
+        self.undo.push(Vec::new());
+        self.open = true;
+    }
+
+    pub fn end(&mut self) {
+        if std::mem::take(&mut self.open) && self.undo.last().is_some_and(Vec::is_empty) {
+            self.undo.pop();
+        }
+    }
+
+    /// Runs `edit` on the universe, remembering every cell it changes.
+    pub fn edit(
+        &mut self,
+        universe: &mut dyn UniverseBackend,
+        edit: impl FnOnce(&mut dyn UniverseBackend),
+    ) {
+        let mut recording = Recording {
+            universe,
+            changes: Vec::new(),
+        };
+        edit(&mut recording);
+        let changes = recording.changes;
+        if changes.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        match self.undo.last_mut() {
+            Some(step) if self.open => step.extend(changes),
+            _ => self.undo.push(changes),
+        }
+    }
+
+    /// Reverts the last undo step, returning whether there was one.
+    pub fn undo(&mut self, universe: &mut dyn UniverseBackend) -> bool {
+        self.end();
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+        for change in step.iter().rev() {
+            universe.set_state(change.row, change.column, change.before);
+        }
+        self.redo.push(step);
+        true
+    }
+
+    /// Reapplies the last step undone, returning whether there was one.
+    pub fn redo(&mut self, universe: &mut dyn UniverseBackend) -> bool {
+        self.end();
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        for change in &step {
+            universe.set_state(change.row, change.column, change.after);
+        }
+        self.undo.push(step);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        *self = History::default();
+    }
+}
+
+/// Passes everything through to a universe, noting each cell `set_state`
+/// changes.
+struct Recording<'a> {
+    universe: &'a mut dyn UniverseBackend,
+    changes: Vec<Change>,
+}
+
+impl UniverseBackend for Recording<'_> {
+    fn rule(&self) -> Rule {
+        self.universe.rule()
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.universe.set_rule(rule);
+    }
+
+    fn state(&self, row: i64, column: i64) -> u8 {
+        self.universe.state(row, column)
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        let before = self.universe.state(row, column);
+        self.universe.set_state(row, column, state);
+        // What the universe actually stored, which may differ from `state`
+        // for cells beyond a dead border.
+        let after = self.universe.state(row, column);
+        if before != after {
+            self.changes.push(Change {
+                row,
+                column,
+                before,
+                after,
+            });
+        }
+    }
+
+    fn tick(&mut self) {
+        self.universe.tick();
+    }
+
+    fn population(&self) -> u64 {
+        self.universe.population()
+    }
+
+    fn period(&self) -> Option<u32> {
+        self.universe.period()
+    }
+
+    fn bounds(&self) -> Option<Selection> {
+        self.universe.bounds()
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        self.universe.step_pow2(k);
+    }
+
+    fn reset(&mut self, soup: Soup) {
+        self.universe.reset(soup);
+    }
+
+    fn kill_all(&mut self) {
+        self.universe.kill_all();
+    }
+}
+
+#[cfg(test)]
+#[path = "history_test.rs"]
+mod history_test;
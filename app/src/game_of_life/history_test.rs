@@ -0,0 +1,72 @@
+use super::*;
+use crate::game_of_life::engine::soup::Soup;
+use crate::game_of_life::engine::universe::Universe;
+
+fn blank() -> Universe {
+    Universe::new(16, 16, Rule::CONWAY, Soup::new(0, 0.0))
+}
+
+#[test]
+fn test_undo_and_redo() {
+    let mut universe = blank();
+    let mut history = History::default();
+    history.edit(&mut universe, |u| u.toggle_cell(3, 4));
+    history.edit(&mut universe, |u| u.insert_glider(8, 8));
+    assert_eq!(universe.state(3, 4), Rule::ALIVE);
+    assert_eq!(universe.state(7, 9), Rule::ALIVE);
+
+    assert!(history.undo(&mut universe));
+    assert_eq!(universe.state(7, 9), Rule::DEAD);
+    assert_eq!(universe.state(3, 4), Rule::ALIVE);
+    assert!(history.undo(&mut universe));
+    assert_eq!(universe.state(3, 4), Rule::DEAD);
+    assert!(!history.undo(&mut universe));
+
+    assert!(history.redo(&mut universe));
+    assert_eq!(universe.state(3, 4), Rule::ALIVE);
+    assert!(history.redo(&mut universe));
+    assert_eq!(universe.state(7, 9), Rule::ALIVE);
+    assert!(!history.redo(&mut universe));
+}
+
+#[test]
+fn test_new_edits_drop_redo() {
+    let mut universe = blank();
+    let mut history = History::default();
+    history.edit(&mut universe, |u| u.toggle_cell(0, 0));
+    history.undo(&mut universe);
+    history.edit(&mut universe, |u| u.toggle_cell(1, 1));
+    assert!(!history.redo(&mut universe));
+    assert_eq!(universe.state(0, 0), Rule::DEAD);
+}
+
+#[test]
+fn test_strokes_undo_together() {
+    let mut universe = blank();
+    let mut history = History::default();
+    history.edit(&mut universe, |u| u.toggle_cell(0, 0));
+    history.begin();
+    for column in 2..6 {
+        history.edit(&mut universe, |u| u.set_state(5, column, Rule::ALIVE));
+    }
+    // Painting over a cell twice restores it properly.
+    history.edit(&mut universe, |u| u.set_state(5, 2, Rule::DEAD));
+    history.end();
+
+    history.undo(&mut universe);
+    assert!((2..6).all(|column| universe.state(5, column) == Rule::DEAD));
+    assert_eq!(universe.state(0, 0), Rule::ALIVE);
+    history.redo(&mut universe);
+    assert_eq!(universe.state(5, 2), Rule::DEAD);
+    assert!((3..6).all(|column| universe.state(5, column) == Rule::ALIVE));
+}
+
+#[test]
+fn test_unchanged_cells_make_no_step() {
+    let mut universe = blank();
+    let mut history = History::default();
+    history.edit(&mut universe, |u| u.set_state(0, 0, Rule::DEAD));
+    history.begin();
+    history.end();
+    assert!(!history.undo(&mut universe));
+}
@@ -0,0 +1,35 @@
+// Runs one generation: each fragment is a cell of the next generation,
+// worked out from the current one.
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
+precision mediump float;
+#endif
+
+uniform vec2 size; // Width and height of the grid, in cells
+uniform float states; // How many states the rule has
+uniform sampler2D cells; // The current generation, with states in red
+// The next state for each count of live neighbours (across) and state
+// (down), in red.
+uniform sampler2D table;
+
+float state(vec2 cell) {
+	// The grid is a torus.
+	vec2 wrapped = mod(cell, size);
+	return floor(texture2D(cells, (wrapped + 0.5) / size).r * 255.0 + 0.5);
+}
+
+void main() {
+	vec2 cell = floor(gl_FragCoord.xy);
+	float neighbors = 0.0;
+	for (int dy = -1; dy <= 1; dy++) {
+		for (int dx = -1; dx <= 1; dx++) {
+			if (dx != 0 || dy != 0) {
+				neighbors += float(state(cell + vec2(dx, dy)) == 1.0);
+			}
+		}
+	}
+	float current = state(cell);
+	float next = texture2D(table, (vec2(neighbors, current) + 0.5) / vec2(9.0, states)).r;
+	gl_FragColor = vec4(next, 0.0, 0.0, 1.0);
+}
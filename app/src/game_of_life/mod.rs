@@ -1,24 +1,73 @@
-mod universe;
+pub mod animation;
+pub mod cli;
+mod drawing_tools;
+/// The simulation, free of the page so the CLI can run it natively.
+pub mod engine;
+mod engine_picker;
+mod gpu;
+mod history;
+mod pattern_library;
+mod pattern_panel;
+mod rule_picker;
+mod soup_picker;
+mod statistics;
+mod theme;
+mod tools;
+mod viewport;
 mod webgl;
 
-use crate::game_of_life::universe::{Universe, UniverseRenderer};
-use crate::game_of_life::webgl::WebGLRenderer;
-use crate::mandelbrot::Bounds;
+use crate::game_of_life::animation::AnimationExport;
+use crate::game_of_life::drawing_tools::{Action, DrawingTools};
+use crate::game_of_life::engine::encoding::{self, AnimationError, AnimationOptions};
+use crate::game_of_life::engine::pattern::Pattern;
+use crate::game_of_life::engine::rule::Rule;
+use crate::game_of_life::engine::soup::Soup;
+use crate::game_of_life::engine::universe::{Backend, Selection, UniverseBackend};
+use crate::game_of_life::engine_picker::EnginePicker;
+use crate::game_of_life::gpu::GpuLife;
+use crate::game_of_life::history::History;
+use crate::game_of_life::pattern_library::PatternLibrary;
+use crate::game_of_life::pattern_panel::PatternPanel;
+use crate::game_of_life::rule_picker::RulePicker;
+use crate::game_of_life::soup_picker::SoupPicker;
+use crate::game_of_life::statistics::{
+    Pacer, PopulationHistory, Statistics, DEFAULT_SPEED, SPEEDS,
+};
+use crate::game_of_life::theme::{Theme, ThemePicker};
+use crate::game_of_life::tools::Tool;
+use crate::game_of_life::webgl::{DomBounds, UniverseRenderer, WebGLRenderer};
 use crate::point2d::Point2D;
 use crate::Footer;
+use leptos::ev;
 use leptos::html::Canvas;
 use leptos::prelude::*;
-use leptos_use::use_raf_fn;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::params::ParamsMap;
+use leptos_router::NavigateOptions;
 use leptos_use::utils::Pausable;
-use universe::DomBounds;
-use web_sys::MouseEvent;
+use leptos_use::{
+    use_event_listener_with_options, use_raf_fn, UseEventListenerOptions, UseRafFnCallbackArgs,
+};
+use web_sys::{MouseEvent, TouchEvent};
+
+/// How much one notch of the mouse wheel zooms by.
+const ZOOM_STEP: f64 = 1.25;
+/// Least time between population counts while the GPU runs, in milliseconds.
+/// Each reads every cell back from it.
+const GPU_SAMPLE_MS: f64 = 250.0;
 
 #[component]
 pub fn instructions() -> impl IntoView {
     let instructions = vec![
-        "Click => Toggle the State of a Cell",
+        "Click or Drag => Use the Picked Tool; Drawing on a Live Cell Erases",
         "Shift + Click => Insert a Pulsar",
         "Ctrl + Click => Insert a Glider",
+        "Alt + Drag => Select Cells to Export, Copy or Transform",
+        "Ctrl + Z / Ctrl + Y => Undo / Redo",
+        "Ctrl + C / X / V, Delete => Copy, Cut, Paste or Clear the Selection",
+        "Click with a Pattern Picked or Loaded => Stamp It",
+        "Scroll or Pinch => Zoom",
+        "Right or Middle Drag, or Touch and Drag => Pan",
     ];
     let instructions = instructions
         .into_iter()
@@ -36,92 +85,598 @@ pub fn instructions() -> impl IntoView {
 
 #[component]
 pub fn game_of_life() -> impl IntoView {
-    let width = 128;
-    let height = 64;
+    // Width and height of the grid, which is also what the canvas shows.
+    let size = RwSignal::new((128, 64));
+    let home = |(width, height): (u32, u32)| Selection {
+        row: 0,
+        column: 0,
+        width,
+        height,
+    };
 
-    let universe = StoredValue::new_local(Universe::new(width, height));
+    let rule = RwSignal::new(Rule::default());
+    // The soup comes from the URL, so a shared link fills the universe the
+    // same way.
+    let query = use_query_map();
+    let url_soup = |query: &ParamsMap| {
+        query
+            .get_str("seed")
+            .map(|seed| Soup::from_query(Some(seed), query.get_str("density")))
+    };
+    let soup = RwSignal::new(query.with_untracked(|query| {
+        url_soup(query).unwrap_or_else(|| Soup::from_query(None, query.get_str("density")))
+    }));
+    let backend = RwSignal::new(Backend::default());
+    let jump = RwSignal::new(0u32);
+    // Index into `SPEEDS`.
+    let speed = RwSignal::new(DEFAULT_SPEED);
+    let pacer = StoredValue::new(Pacer::default());
+    let generation = RwSignal::new(0u64);
+    let population = RwSignal::new(0u64);
+    let populations = RwSignal::new(PopulationHistory::default());
+    let period = RwSignal::new(None::<u32>);
+    let auto_pause = RwSignal::new(false);
+    let theme = RwSignal::new(Theme::default());
+    let universe = StoredValue::new_local({
+        let (width, height) = size.get_untracked();
+        backend
+            .get_untracked()
+            .create(width, height, rule.get_untracked(), soup.get_untracked())
+    });
     let renderer = StoredValue::new_local(None::<WebGLRenderer>);
     let canvas: NodeRef<Canvas> = NodeRef::new();
+    let pending = RwSignal::new(None::<Pattern>);
+    let selection = RwSignal::new(None::<Selection>);
+    let selection_start = StoredValue::new(None::<(i64, i64)>);
+    let hover = RwSignal::new(None::<(i64, i64)>);
+    let tool = RwSignal::new(Tool::default());
+    let history = StoredValue::new_local(History::default());
+    let clipboard = RwSignal::new(None::<Pattern>);
+    // The state a brush stroke paints, and the last cell it painted.
+    let painting = StoredValue::new(None::<(u8, (i64, i64))>);
+    // Where a line or rectangle being dragged out starts.
+    let anchor = RwSignal::new(None::<(i64, i64)>);
+    // Where on the page a drag or touches last moved the view from.
+    let panning = StoredValue::new(None::<(i32, i32)>);
+    let touches = StoredValue::new(Vec::<(i32, i32)>::new());
+    // When the population was last counted, in milliseconds.
+    let last_sample = StoredValue::new(f64::NEG_INFINITY);
 
-    let render = move || {
+    let draw = move || {
         renderer.update_value(|r| {
             if (if let Some(r) = r {
-                universe.with_value(|u| r.render(u))
+                universe.with_value(|u| r.render(u.as_ref()))
             } else {
                 Err(())
-            }).is_err() {
+            })
+            .is_err()
+            {
                 if let Some(canvas_ref) = canvas.get() {
+                    let (width, height) = size.get_untracked();
+                    let view = r.as_ref().map(WebGLRenderer::view);
                     let mut new_renderer = WebGLRenderer::new(canvas_ref, width, height);
-                    let _ = universe.with_value(|u| new_renderer.render(u));
+                    new_renderer.set_theme(theme.get_untracked());
+                    if let Some(view) = view {
+                        *new_renderer.view_mut() = view;
+                    }
+                    let _ = universe.with_value(|u| new_renderer.render(u.as_ref()));
                     *r = Some(new_renderer);
                 }
             }
         });
     };
+    let sample = move || {
+        last_sample.set_value(js_sys::Date::now());
+        let (count, cycle) = universe.with_value(|u| (u.population(), u.period()));
+        if population.get_untracked() != count {
+            population.set(count);
+        }
+        if period.get_untracked() != cycle {
+            period.set(cycle);
+        }
+    };
+    let render = move || {
+        draw();
+        sample();
+    };
 
     Effect::new(move |_| {
         // Initial creation when canvas mounts
         if let Some(canvas) = canvas.get() {
             if renderer.with_value(|r| r.is_none()) {
-                renderer.set_value(Some(WebGLRenderer::new(canvas, width, height)));
+                let (width, height) = size.get_untracked();
+                let mut new_renderer = WebGLRenderer::new(canvas, width, height);
+                new_renderer.set_theme(theme.get_untracked());
+                renderer.set_value(Some(new_renderer));
                 render();
             }
         }
     });
 
+    Effect::new(move |_| {
+        let rule = rule.get();
+        if !backend.get_untracked().supports(rule) {
+            backend.set(Backend::default());
+        }
+        universe.update_value(|universe| universe.set_rule(rule));
+    });
+
+    // Carry the visible cells over when switching backends or resizing.
+    Effect::new(move |previous: Option<(Backend, (u32, u32))>| {
+        let (backend, size) = (backend.get(), size.get());
+        if let Some((previous_backend, previous_size)) = previous {
+            if (previous_backend, previous_size) != (backend, size) {
+                history.update_value(History::clear);
+                universe.update_value(|universe| {
+                    let cells = universe.to_pattern(home(previous_size));
+                    let gl = renderer.with_value(|r| r.as_ref().map(WebGLRenderer::context));
+                    let mut next = gpu::create(
+                        backend,
+                        size.0,
+                        size.1,
+                        universe.rule(),
+                        soup.get_untracked(),
+                        gl.as_ref(),
+                    );
+                    next.kill_all();
+                    next.insert_pattern(&cells, 0, 0);
+                    *universe = next;
+                });
+                if previous_size != size {
+                    selection.set(None);
+                    renderer.set_value(None);
+                }
+                render();
+            }
+        }
+        (backend, size)
+    });
+
+    Effect::new(move |_| {
+        let theme = theme.get();
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.set_theme(theme);
+            }
+        });
+        render();
+    });
+
+    // Lets the renderer age the cells in view by the generations just run.
+    let age = move |generations: u64| {
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.age(generations);
+            }
+        });
+    };
+
+    Effect::new(move |_| {
+        let selection = selection.get();
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.set_selection(selection);
+            }
+        });
+        render();
+    });
+
+    // Ghost of the shape being drawn, or of the pending pattern under the
+    // cursor.
+    Effect::new(move |_| {
+        let shape = anchor
+            .get()
+            .zip(hover.get())
+            .and_then(|(from, to)| tool.get().shape(from, to));
+        let preview = match shape {
+            Some(cells) => Some(tools::to_pattern(&cells)),
+            None => pending
+                .get()
+                .zip(hover.get())
+                .map(|(pattern, (row, column))| (pattern, row, column)),
+        };
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.set_preview(preview);
+            }
+        });
+        render();
+    });
+
+    // Runs `ticks` ticks and charts the population they leave.
+    let advance = move |ticks: u32| {
+        let k = jump.get_untracked();
+        history.update_value(History::clear);
+        universe.update_value(|universe| {
+            for _ in 0..ticks {
+                universe.step_pow2(k);
+            }
+        });
+        generation.update(|generation| *generation += u64::from(ticks) << k);
+        age(u64::from(ticks) << k);
+        draw();
+        let on_gpu = universe.with_value(|u| GpuLife::of(u.as_ref()).is_some());
+        if !on_gpu || js_sys::Date::now() - last_sample.get_value() >= GPU_SAMPLE_MS {
+            sample();
+            populations.update(|populations| populations.push(population.get_untracked()));
+        }
+    };
+
     let Pausable {
         pause,
         resume,
         is_active,
-    } = use_raf_fn(move |_| {
-        universe.update_value(|universe| universe.tick());
+    } = use_raf_fn(move |frame: UseRafFnCallbackArgs| {
+        let speed = f64::from(SPEEDS[speed.get_untracked()]);
+        let ticks = pacer
+            .try_update_value(|pacer| pacer.ticks(speed, jump.get_untracked(), frame.delta))
+            .unwrap_or(0);
+        if ticks > 0 {
+            advance(ticks);
+        }
+    });
+
+    {
+        let pause = pause.clone();
+        Effect::new(move |_| {
+            if auto_pause.get() && is_active.get() && period.get().is_some() {
+                pause();
+            }
+        });
+    }
+
+    let tick = move |_| advance(1);
+    // Starts counting generations again from a fresh universe.
+    let restart = move |start: &dyn Fn(&mut dyn UniverseBackend)| {
+        history.update_value(History::clear);
+        universe.update_value(|universe| start(universe.as_mut()));
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.clear_ages();
+            }
+        });
+        generation.set(0);
+        populations.update(PopulationHistory::clear);
         render();
+    };
+    let reset = move |_| restart(&|universe| universe.reset(soup.get_untracked()));
+    let kill_all = move |_| restart(&|universe| universe.kill_all());
+
+    // Follow the URL when it changes, e.g. going back to an earlier seed.
+    Effect::new(move |_| {
+        if let Some(picked) = query.with(url_soup) {
+            if picked != soup.get_untracked() {
+                soup.set(picked);
+            }
+        }
     });
 
-    let tick = move |_| {
-        universe.update_value(|universe| universe.tick());
+    // Write a new soup to the URL and fill the universe with it. The first
+    // one replaces the entry so the page without a seed isn't kept.
+    let navigate = use_navigate();
+    Effect::new(move |previous: Option<Soup>| {
+        let soup = soup.get();
+        if query.with_untracked(url_soup) != Some(soup) {
+            navigate(
+                &soup.to_query(),
+                NavigateOptions {
+                    replace: previous.is_none(),
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        }
+        if previous.is_some_and(|previous| previous != soup) {
+            restart(&|universe| universe.reset(soup));
+        }
+        soup
+    });
+
+    // Changes cells in a way that can be undone.
+    let edit = move |change: &dyn Fn(&mut dyn UniverseBackend)| {
+        history.update_value(|history| {
+            universe.update_value(|universe| history.edit(universe.as_mut(), change))
+        });
         render();
     };
-    let reset = move |_| {
-        universe.update_value(|universe| universe.reset());
+
+    let dom_bounds = move || {
+        let rect = canvas.get()?.get_bounding_client_rect();
+        Some(DomBounds {
+            origin: Point2D {
+                x: rect.x(),
+                y: rect.y(),
+            },
+            width: rect.width(),
+            height: rect.height(),
+        })
+    };
+
+    let cell_at = move |event: &MouseEvent| {
+        let bounding_rect = dom_bounds()?;
+        renderer.with_value(|r| {
+            r.as_ref().map(|r| {
+                r.get_cell_index(
+                    bounding_rect,
+                    Point2D::<i32> {
+                        x: event.x(),
+                        y: event.y(),
+                    },
+                )
+            })
+        })
+    };
+
+    // Drags what is under `from` on the page to `to`, zooming around it by
+    // `factor`.
+    let move_view = move |from: (i32, i32), to: (i32, i32), factor: f64| {
+        let Some(bounding_rect) = dom_bounds() else {
+            return;
+        };
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                let (from_x, from_y) = r.canvas_point(
+                    &bounding_rect,
+                    Point2D {
+                        x: from.0,
+                        y: from.1,
+                    },
+                );
+                let (to_x, to_y) = r.canvas_point(&bounding_rect, Point2D { x: to.0, y: to.1 });
+                let view = r.view_mut();
+                view.pan(to_x - from_x, to_y - from_y);
+                view.zoom_at(to_x, to_y, factor);
+            }
+        });
         render();
     };
-    let kill_all = move |_| {
-        universe.update_value(|universe| universe.kill_all());
+
+    let reset_view = move |_| {
+        renderer.update_value(|r| {
+            if let Some(r) = r {
+                r.reset_view();
+            }
+        });
         render();
     };
 
-    let on_click = move |event: MouseEvent| {
-        if let Some(canvas) = canvas.get() {
-            let rect = canvas.get_bounding_client_rect();
-            let bounding_rect = DomBounds {
-                origin: Point2D {
-                    x: rect.x(),
-                    y: rect.y(),
-                },
-                width: rect.width(),
-                height: rect.height(),
+    // Zooming and pinching must be able to stop the page scrolling, which
+    // passive listeners can't.
+    let active = UseEventListenerOptions::default().passive(false);
+    let _ = use_event_listener_with_options(
+        canvas,
+        ev::wheel,
+        move |event| {
+            event.prevent_default();
+            let point = (event.x(), event.y());
+            let factor = if event.delta_y() < 0.0 {
+                ZOOM_STEP
+            } else {
+                1.0 / ZOOM_STEP
             };
-            let (x, y) = WebGLRenderer::get_cell_index(
-                bounding_rect,
-                Bounds { width, height },
-                Point2D::<i32> {
-                    x: event.x(),
-                    y: event.y(),
-                },
-            );
+            move_view(point, point, factor);
+        },
+        active,
+    );
+
+    let touch_points = |event: &TouchEvent| {
+        let list = event.touches();
+        (0..list.length())
+            .filter_map(|i| list.get(i))
+            .map(|touch| (touch.client_x(), touch.client_y()))
+            .collect::<Vec<_>>()
+    };
+    let _ = use_event_listener_with_options(
+        canvas,
+        ev::touchmove,
+        move |event| {
+            event.prevent_default();
+            let now = touch_points(&event);
+            match (touches.get_value().as_slice(), now.as_slice()) {
+                (&[from], &[to]) => move_view(from, to, 1.0),
+                (&[a, b], &[c, d]) => {
+                    let middle = |p: (i32, i32), q: (i32, i32)| ((p.0 + q.0) / 2, (p.1 + q.1) / 2);
+                    let distance = |p: (i32, i32), q: (i32, i32)| {
+                        ((p.0 - q.0) as f64).hypot((p.1 - q.1) as f64)
+                    };
+                    if distance(a, b) > 0.0 {
+                        move_view(middle(a, b), middle(c, d), distance(c, d) / distance(a, b));
+                    }
+                }
+                _ => {}
+            }
+            touches.set_value(now);
+        },
+        active,
+    );
 
-            universe.update_value(|universe| {
-                if event.shift_key() {
+    let on_click = move |event: MouseEvent| {
+        if event.alt_key() {
+            return;
+        }
+        if let Some((x, y)) = cell_at(&event) {
+            // Plain clicks were handled by the tool on mouse down.
+            let pattern = pending.get_untracked();
+            // A pattern's rule isn't an edit undo can take back, so it is
+            // switched to before stamping.
+            if let Some(pattern_rule) = pattern.as_ref().and_then(|pattern| pattern.rule) {
+                universe.update_value(|universe| universe.set_rule(pattern_rule));
+                rule.set(pattern_rule);
+            }
+            edit(&|universe| {
+                if let Some(pattern) = &pattern {
+                    universe.insert_pattern(pattern, x, y);
+                } else if event.shift_key() {
                     universe.insert_pulsar(x, y);
                 } else if event.ctrl_key() {
                     universe.insert_glider(x, y);
-                } else {
-                    universe.toggle_cell(x, y);
                 }
             });
+            if pattern.is_some() {
+                pending.set(None);
+            }
+        }
+    };
+
+    let stop_drawing = move || {
+        painting.set_value(None);
+        anchor.set(None);
+        history.update_value(History::end);
+    };
+
+    let on_mouse_down = move |event: MouseEvent| {
+        // Middle or right button.
+        if matches!(event.button(), 1 | 2) {
+            event.prevent_default();
+            panning.set_value(Some((event.x(), event.y())));
+            return;
+        }
+        let Some(cell) = cell_at(&event) else {
+            return;
+        };
+        let select = || {
+            selection_start.set_value(Some(cell));
+            selection.set(Some(Selection::from_corners(cell, cell)));
+        };
+        if event.alt_key() {
+            select();
+            return;
+        }
+        if event.shift_key() || event.ctrl_key() || pending.with_untracked(Option::is_some) {
+            return;
+        }
+        match tool.get_untracked() {
+            Tool::Draw | Tool::Erase => {
+                history.update_value(History::begin);
+                // Drawing toggles the first cell, then paints what it became.
+                edit(&|universe| match tool.get_untracked() {
+                    Tool::Erase => universe.set_state(cell.0, cell.1, Rule::DEAD),
+                    _ => universe.toggle_cell(cell.0, cell.1),
+                });
+                let state = universe.with_value(|universe| universe.state(cell.0, cell.1));
+                painting.set_value(Some((state, cell)));
+            }
+            Tool::Line | Tool::Rectangle => anchor.set(Some(cell)),
+            Tool::Select => select(),
+        }
+    };
+
+    let on_mouse_up = move |event: MouseEvent| {
+        panning.set_value(None);
+        if let (Some(from), Some(to)) = (anchor.get_untracked(), cell_at(&event)) {
+            if let Some(cells) = tool.get_untracked().shape(from, to) {
+                edit(&|universe| {
+                    for &(row, column) in &cells {
+                        universe.set_state(row, column, Rule::ALIVE);
+                    }
+                });
+            }
+        }
+        stop_drawing();
+        if let (Some(start), Some(end)) = (selection_start.get_value(), cell_at(&event)) {
+            selection.set(Some(Selection::from_corners(start, end)));
+        }
+        selection_start.set_value(None);
+    };
+
+    let on_mouse_move = move |event: MouseEvent| {
+        if let Some(from) = panning.get_value() {
+            let to = (event.x(), event.y());
+            move_view(from, to, 1.0);
+            panning.set_value(Some(to));
+        }
+        let cell = cell_at(&event);
+        if let (Some((state, last)), Some(cell)) = (painting.get_value(), cell) {
+            // Fill in the cells between events so fast strokes have no gaps.
+            edit(&|universe| {
+                for (row, column) in tools::line(last, cell) {
+                    universe.set_state(row, column, state);
+                }
+            });
+            painting.set_value(Some((state, cell)));
+        }
+        if let (Some(start), Some(end)) = (selection_start.get_value(), cell) {
+            selection.set(Some(Selection::from_corners(start, end)));
+        }
+        if hover.get_untracked() != cell {
+            hover.set(cell);
+        }
+    };
+
+    let run_action = move |action: Action| {
+        let selected = selection.get_untracked();
+        let copy = move || selected.map(|s| universe.with_value(|universe| universe.to_pattern(s)));
+        // Swaps the selected cells for a transformed copy of them.
+        let transform = move |transform: fn(&Pattern) -> Pattern| {
+            if let (Some(s), Some(cells)) = (selected, copy()) {
+                let cells = transform(&cells);
+                edit(&|universe| {
+                    universe.clear(s);
+                    universe.insert_pattern(&cells, s.row, s.column);
+                });
+                selection.set(Some(Selection {
+                    width: cells.width,
+                    height: cells.height,
+                    ..s
+                }));
+            }
+        };
+        match action {
+            Action::Undo | Action::Redo => {
+                history.update_value(|history| {
+                    universe.update_value(|universe| {
+                        if action == Action::Undo {
+                            history.undo(universe.as_mut());
+                        } else {
+                            history.redo(universe.as_mut());
+                        }
+                    })
+                });
+                render();
+            }
+            Action::Copy => clipboard.set(copy()),
+            Action::Cut => {
+                clipboard.set(copy());
+                if let Some(s) = selected {
+                    edit(&|universe| universe.clear(s));
+                }
+            }
+            Action::Paste => pending.set(clipboard.get_untracked()),
+            Action::Delete => {
+                if let Some(s) = selected {
+                    edit(&|universe| universe.clear(s));
+                }
+            }
+            Action::RotateSelection => transform(Pattern::rotate_clockwise),
+            Action::FlipSelectionHorizontally => transform(Pattern::flip_horizontal),
+            Action::FlipSelectionVertically => transform(Pattern::flip_vertical),
+        }
+    };
+
+    let export = move |_| {
+        let selection = selection
+            .get_untracked()
+            .unwrap_or(home(size.get_untracked()));
+        universe.with_value(|universe| universe.to_pattern(selection))
+    };
+
+    // Records the selection, or the whole universe, running it on as it goes.
+    let record = move |options: AnimationOptions| -> Result<Vec<u8>, AnimationError> {
+        let area = selection
+            .get_untracked()
+            .unwrap_or(home(size.get_untracked()));
+        let (ticked, recorded) = universe
+            .try_update_value(|universe| encoding::record(universe.as_mut(), area, &options))
+            .unwrap_or((0, Ok(Vec::new())));
+        // Whatever ran before a failure still counts.
+        if ticked > 0 {
+            history.update_value(History::clear);
+            generation.update(|generation| *generation += u64::from(ticked));
+            age(u64::from(ticked));
             render();
+            populations.update(|populations| populations.push(population.get_untracked()));
         }
+        recorded
     };
 
     view! {
@@ -132,7 +687,21 @@ pub fn game_of_life() -> impl IntoView {
                 </header>
                 <section class="life-area">
                     <div class="game-of-life">
-                        <canvas node_ref=canvas on:click=on_click />
+                        <canvas
+                            node_ref=canvas
+                            on:click=on_click
+                            on:mousedown=on_mouse_down
+                            on:mouseup=on_mouse_up
+                            on:mousemove=on_mouse_move
+                            on:mouseleave=move |_| {
+                                hover.set(None);
+                                panning.set_value(None);
+                                stop_drawing();
+                            }
+                            on:contextmenu=|event| event.prevent_default()
+                            on:touchstart=move |event| touches.set_value(touch_points(&event))
+                            on:touchend=move |event| touches.set_value(touch_points(&event))
+                        />
                     </div>
                     <div class="life-buttons">
                         <Show when=move || { !is_active.get() }
@@ -148,7 +717,35 @@ pub fn game_of_life() -> impl IntoView {
                         <button class="game-button" on:click=tick >{ "Tick" }</button>
                         <button class="game-button" on:click=reset >{ "Reset" }</button>
                         <button class="game-button" on:click=kill_all >{ "KillAll" }</button>
+                        <button class="game-button" on:click=reset_view >{ "Reset View" }</button>
                     </div>
+                    <Statistics
+                        speed
+                        jump
+                        generation
+                        population
+                        history=populations
+                        period
+                        auto_pause
+                    />
+                    <SoupPicker soup />
+                    <ThemePicker theme gpu=Signal::derive(move || backend.get() == Backend::Gpu) />
+                    <RulePicker rule />
+                    <EnginePicker backend jump size rule />
+                    <DrawingTools
+                        tool
+                        has_selection=Signal::derive(move || selection.with(Option::is_some))
+                        can_paste=Signal::derive(move || clipboard.with(Option::is_some))
+                        action=run_action
+                    />
+                    <PatternLibrary pending />
+                    <PatternPanel
+                        pending
+                        has_selection=Signal::derive(move || selection.with(Option::is_some))
+                        export
+                        clear_selection=move |_| selection.set(None)
+                    />
+                    <AnimationExport record />
                     <Instructions />
                 </section>
             </section>
@@ -0,0 +1,64 @@
+use leptos::ev;
+use leptos::leptos_dom::helpers::window_event_listener;
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::engine::library::{self, LIBRARY};
+use super::engine::pattern::Pattern;
+
+/// Palette of built-in patterns. Picking one makes it the `pending` pattern,
+/// which can then be rotated and flipped from the keyboard before stamping.
+#[component]
+pub fn PatternLibrary(#[prop(into)] pending: RwSignal<Option<Pattern>>) -> impl IntoView {
+    Effect::new(move |_| {
+        let handle_keydown = window_event_listener(ev::keydown, move |ev| {
+            // Leave typing in the rule and pattern boxes alone.
+            let typing = ev
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+            // Ctrl or Cmd + V pastes rather than flipping.
+            let command = ev.ctrl_key() || ev.meta_key();
+            if typing || command || pending.with_untracked(Option::is_none) {
+                return;
+            }
+            let transform: fn(&Pattern) -> Pattern = match ev.code().as_str() {
+                "KeyR" => Pattern::rotate_clockwise,
+                "KeyF" => Pattern::flip_horizontal,
+                "KeyV" => Pattern::flip_vertical,
+                "Escape" => {
+                    pending.set(None);
+                    return;
+                }
+                _ => return,
+            };
+            pending.update(|pattern| *pattern = pattern.as_ref().map(transform));
+        });
+        on_cleanup(move || handle_keydown.remove());
+    });
+
+    let is_pending = move |name: &str| {
+        pending.with(|pattern| {
+            pattern
+                .as_ref()
+                .is_some_and(|pattern| pattern.name.as_deref() == Some(name))
+        })
+    };
+
+    view! {
+        <div class="life-library">
+            {LIBRARY.iter().map(|(name, _)| {
+                view! {
+                    <button
+                        class="library-button"
+                        class:active=move || is_pending(name)
+                        on:click=move |_| pending.set(library::load(name))
+                    >
+                        {*name}
+                    </button>
+                }
+            }).collect_view()}
+            <span class="library-hint">"R => Rotate, F => Flip Horizontally, V => Flip Vertically, Esc => Cancel"</span>
+        </div>
+    }
+}
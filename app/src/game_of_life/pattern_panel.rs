@@ -0,0 +1,116 @@
+use gloo_file::futures::read_as_text;
+use gloo_file::File;
+use leptos::ev;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use super::engine::pattern::Pattern;
+
+/// Import and export of RLE and `.cells` patterns. A loaded pattern waits in
+/// `pending` until the user clicks where to place it.
+#[component]
+pub fn PatternPanel(
+    #[prop(into)] pending: RwSignal<Option<Pattern>>,
+    #[prop(into)] has_selection: Signal<bool>,
+    #[prop(into)] export: Callback<(), Pattern>,
+    #[prop(into)] clear_selection: Callback<()>,
+) -> impl IntoView {
+    let text = RwSignal::new(String::new());
+    let (status, set_status) = signal(String::new());
+
+    // Placing or cancelling the pattern clears its prompt.
+    Effect::new(move |_| {
+        if pending.with(Option::is_none) {
+            set_status(String::new());
+        }
+    });
+
+    let load = move |source: String| match Pattern::parse(&source) {
+        Ok(pattern) => {
+            let name = pattern
+                .name
+                .clone()
+                .unwrap_or_else(|| "pattern".to_string());
+            set_status(format!(
+                "Click a cell to place {name} ({}x{})",
+                pattern.width, pattern.height
+            ));
+            pending.set(Some(pattern));
+        }
+        Err(e) => set_status(format!("Invalid pattern: {e}")),
+    };
+
+    let on_file_upload = move |ev: ev::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+        if let Some(file) = input.files().and_then(|files| files.get(0)).map(File::from) {
+            spawn_local(async move {
+                match read_as_text(&file).await {
+                    Ok(contents) => {
+                        text.set(contents.clone());
+                        load(contents);
+                    }
+                    Err(e) => leptos::logging::error!("Error reading file: {:?}", e),
+                }
+            });
+        }
+    };
+
+    let on_export = move |format: &str| {
+        let what = if has_selection.get_untracked() {
+            "selection"
+        } else {
+            "universe"
+        };
+        let pattern = export.run(());
+        text.set(match format {
+            "RLE" => pattern.to_rle(),
+            _ => pattern.to_plaintext(),
+        });
+        set_status(format!("Exported the {what} as {format}"));
+    };
+
+    view! {
+        <div class="life-patterns">
+            <textarea
+                class="pattern-text"
+                placeholder="Paste an RLE or .cells pattern"
+                prop:value=move || text.get()
+                on:input=move |ev| text.set(event_target_value(&ev))
+            />
+            <div class="life-buttons">
+                <button class="game-button" on:click=move |_| load(text.get_untracked())>
+                    "Load Pattern"
+                </button>
+                <label class="game-button">
+                    "Upload"
+                    <input
+                        type="file"
+                        accept=".rle,.cells,.txt"
+                        style="display: none"
+                        on:change=on_file_upload
+                    />
+                </label>
+                <button class="game-button" on:click=move |_| on_export("RLE")>
+                    "Export RLE"
+                </button>
+                <button class="game-button" on:click=move |_| on_export(".cells")>
+                    "Export .cells"
+                </button>
+                <Show when=move || has_selection.get()>
+                    <button class="game-button" on:click=move |_| clear_selection.run(())>
+                        "Clear Selection"
+                    </button>
+                </Show>
+                <Show when=move || pending.with(Option::is_some)>
+                    <button
+                        class="game-button"
+                        on:click=move |_| pending.set(None)
+                    >
+                        "Cancel"
+                    </button>
+                </Show>
+            </div>
+            <span class="pattern-status">{move || status.get()}</span>
+        </div>
+    }
+}
@@ -0,0 +1,55 @@
+use leptos::prelude::*;
+
+use super::engine::rule::{Rule, PRESETS};
+
+#[component]
+pub fn RulePicker(#[prop(into)] rule: RwSignal<Rule>) -> impl IntoView {
+    let (text, set_text) = signal(rule.get_untracked().to_string());
+    let (error, set_error) = signal(None::<String>);
+
+    // Keep the text box in sync when a preset is picked.
+    Effect::new(move |_| {
+        set_text(rule.get().to_string());
+        set_error(None);
+    });
+
+    let apply = move |value: String| match value.parse::<Rule>() {
+        Ok(parsed) => {
+            rule.set(parsed);
+            set_error(None);
+        }
+        Err(e) => {
+            set_text(value);
+            set_error(Some(e.to_string()));
+        }
+    };
+
+    view! {
+        <div class="life-rule">
+            <select
+                class="rule-select"
+                prop:value=move || rule.get().to_string()
+                on:change=move |ev| apply(event_target_value(&ev))
+            >
+                {PRESETS.iter().map(|(name, notation)| {
+                    view! { <option value=*notation>{format!("{name} ({notation})")}</option> }
+                }).collect_view()}
+                <Show when=move || {
+                    let current = rule.get().to_string();
+                    !PRESETS.iter().any(|(_, notation)| *notation == current)
+                }>
+                    <option value=move || rule.get().to_string()>"Custom"</option>
+                </Show>
+            </select>
+            <input
+                type="text"
+                class="rule-input"
+                class:invalid=move || error.get().is_some()
+                title=move || error.get().unwrap_or_else(|| "Rule in B/S notation, e.g. B36/S23".to_string())
+                prop:value=move || text.get()
+                on:change=move |ev| apply(event_target_value(&ev))
+            />
+            <span class="rule-error">{move || error.get()}</span>
+        </div>
+    }
+}
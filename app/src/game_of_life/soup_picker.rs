@@ -0,0 +1,47 @@
+use leptos::prelude::*;
+
+use super::engine::soup::Soup;
+
+/// Step the density slider moves in.
+const DENSITY_STEP: f64 = 0.05;
+
+/// Density slider and seed box for the soup Reset fills the universe with,
+/// and a button to step on to the next seed.
+#[component]
+pub fn SoupPicker(#[prop(into)] soup: RwSignal<Soup>) -> impl IntoView {
+    view! {
+        <div class="life-soup">
+            <label class="soup-density">
+                {move || format!("Density: {:.0}%", soup.get().density() * 100.0)}
+                <input
+                    type="range"
+                    min="0"
+                    max="1"
+                    step=DENSITY_STEP
+                    prop:value=move || soup.get().density()
+                    on:change=move |ev| {
+                        if let Ok(density) = event_target_value(&ev).parse::<f64>() {
+                            soup.update(|soup| *soup = Soup::new(soup.seed(), density));
+                        }
+                    }
+                />
+            </label>
+            <label class="soup-seed">
+                "Seed: "
+                <input
+                    type="number"
+                    min="0"
+                    prop:value=move || soup.get().seed()
+                    on:change=move |ev| {
+                        if let Ok(seed) = event_target_value(&ev).parse::<u64>() {
+                            soup.update(|soup| *soup = Soup::new(seed, soup.density()));
+                        }
+                    }
+                />
+            </label>
+            <button class="game-button" on:click=move |_| soup.update(|soup| *soup = soup.next())>
+                "Next Seed"
+            </button>
+        </div>
+    }
+}
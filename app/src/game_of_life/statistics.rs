@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use leptos::prelude::*;
+
+/// Speeds the slider steps through, in ticks per second. A tick is one
+/// generation, or a Hashlife jump of many.
+pub const SPEEDS: [u32; 14] = [
+    1, 2, 5, 10, 20, 30, 60, 120, 250, 500, 1000, 2000, 5000, 10000,
+];
+/// Sixty ticks per second, about one a frame.
+pub const DEFAULT_SPEED: usize = 6;
+
+/// Most generations run in one frame, so a slow backend falls behind the
+/// speed asked for rather than freezing the page. A jump bigger than this
+/// still runs, one a frame.
+const MAX_GENERATIONS_PER_FRAME: u32 = 64;
+/// Longest gap between frames made up for, in milliseconds. A pause or a
+/// hidden tab isn't caught up on.
+const MAX_FRAME_TIME: f64 = 100.0;
+
+/// How many population samples the chart shows.
+const SAMPLES: usize = 200;
+const CHART_WIDTH: f64 = 200.0;
+const CHART_HEIGHT: f64 = 48.0;
+
+/// Works out how many ticks each animation frame runs, so the universe
+/// keeps a steady speed whatever the frame rate.
+#[derive(Debug, Default)]
+pub struct Pacer {
+    /// Ticks due but not yet run.
+    owed: f64,
+}
+
+impl Pacer {
+    /// The ticks due after `elapsed` milliseconds at `speed` ticks per
+    /// second. A tick jumping `2^jump` generations multiplies the speed
+    /// rather than eating into it, but counts that many against the frame's
+    /// budget.
+    pub fn ticks(&mut self, speed: f64, jump: u32, elapsed: f64) -> u32 {
+        let most = Self::max_ticks(jump);
+        self.owed += speed * elapsed.min(MAX_FRAME_TIME) / 1000.0;
+        let ticks = self.owed.floor();
+        if ticks >= f64::from(most) {
+            self.owed = 0.0;
+            return most;
+        }
+        self.owed -= ticks;
+        ticks as u32
+    }
+
+    /// Most ticks of `2^jump` generations that fit in a frame.
+    pub fn max_ticks(jump: u32) -> u32 {
+        MAX_GENERATIONS_PER_FRAME
+            .checked_shr(jump)
+            .unwrap_or(0)
+            .max(1)
+    }
+}
+
+/// The population after each of the last few frames that ran.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PopulationHistory {
+    samples: VecDeque<u64>,
+}
+
+impl PopulationHistory {
+    pub fn push(&mut self, population: u64) {
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(population);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn peak(&self) -> u64 {
+        self.samples.iter().copied().max().unwrap_or(0)
+    }
+
+    /// The samples as SVG polyline points, oldest on the left and scaled so
+    /// the peak touches the top of the chart.
+    pub fn points(&self) -> String {
+        let peak = self.peak().max(1) as f64;
+        let step = CHART_WIDTH / (SAMPLES - 1) as f64;
+        self.samples
+            .iter()
+            .enumerate()
+            .map(|(i, &population)| {
+                let y = CHART_HEIGHT * (1.0 - population as f64 / peak);
+                format!("{:.1},{:.1}", i as f64 * step, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// How a cycle the universe has settled into is described.
+pub fn describe_period(period: Option<u32>) -> String {
+    match period {
+        None => String::from("Not Cycling"),
+        Some(1) => String::from("Still Life"),
+        Some(period) => format!("Period {period}"),
+    }
+}
+
+/// Speed slider, generation counter and a chart of the population over
+/// time, which flattens out once a soup settles down, along with any cycle
+/// the universe has entered.
+#[component]
+pub fn Statistics(
+    #[prop(into)] speed: RwSignal<usize>,
+    #[prop(into)] jump: Signal<u32>,
+    #[prop(into)] generation: Signal<u64>,
+    #[prop(into)] population: Signal<u64>,
+    #[prop(into)] history: Signal<PopulationHistory>,
+    #[prop(into)] period: Signal<Option<u32>>,
+    #[prop(into)] auto_pause: RwSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <div class="life-stats">
+            <label class="stats-speed">
+                {move || match jump.get() {
+                    0 => format!("Speed: {} gen/s", SPEEDS[speed.get()]),
+                    k => format!("Speed: {} jumps/s of 2^{k}", SPEEDS[speed.get()]),
+                }}
+                <input
+                    type="range"
+                    min="0"
+                    max=SPEEDS.len() - 1
+                    prop:value=move || speed.get()
+                    on:input=move |ev| {
+                        if let Ok(picked) = event_target_value(&ev).parse::<usize>() {
+                            speed.set(picked.min(SPEEDS.len() - 1));
+                        }
+                    }
+                />
+            </label>
+            <span class="stats-count">{move || format!("Generation: {}", generation.get())}</span>
+            <span class="stats-count">{move || format!("Population: {}", population.get())}</span>
+            <svg
+                class="stats-chart"
+                viewBox=format!("0 0 {CHART_WIDTH} {CHART_HEIGHT}")
+                preserveAspectRatio="none"
+            >
+                <polyline points=move || history.with(PopulationHistory::points) />
+            </svg>
+            <span class="stats-peak">{move || format!("Peak: {}", history.with(PopulationHistory::peak))}</span>
+            <span class="stats-period" class:cycling=move || period.get().is_some()>
+                {move || describe_period(period.get())}
+            </span>
+            <label class="stats-pause">
+                <input
+                    type="checkbox"
+                    prop:checked=move || auto_pause.get()
+                    on:change=move |ev| auto_pause.set(event_target_checked(&ev))
+                />
+                "Pause on Cycle"
+            </label>
+        </div>
+    }
+}
+
+#[cfg(test)]
+#[path = "statistics_test.rs"]
+mod statistics_test;
@@ -0,0 +1,72 @@
+use super::*;
+
+#[test]
+fn test_pacer_spreads_ticks_over_frames() {
+    let mut pacer = Pacer::default();
+    // Ten generations a second at 40 frames a second.
+    let ticks: u32 = (0..40).map(|_| pacer.ticks(10.0, 0, 25.0)).sum();
+    assert_eq!(ticks, 10);
+}
+
+#[test]
+fn test_pacer_runs_several_ticks_a_frame() {
+    let mut pacer = Pacer::default();
+    assert_eq!(pacer.ticks(1000.0, 0, 20.0), 20);
+    // Jumps multiply the speed rather than dividing it between them.
+    assert_eq!(pacer.ticks(100.0, 3, 20.0), 2);
+}
+
+#[test]
+fn test_pacer_runs_jumps_at_the_slider_rate() {
+    let mut pacer = Pacer::default();
+    // Five jumps a second at 20 frames a second skip most frames.
+    let ticks: Vec<u32> = (0..20).map(|_| pacer.ticks(5.0, 20, 50.0)).collect();
+    assert_eq!(ticks.iter().sum::<u32>(), 5);
+    assert_eq!(ticks.iter().filter(|&&t| t == 0).count(), 15);
+    assert_eq!(pacer.ticks(5.0, 20, 0.0), 0);
+}
+
+#[test]
+fn test_pacer_limits_generations_per_frame() {
+    let mut pacer = Pacer::default();
+    assert_eq!(pacer.ticks(10000.0, 6, 20.0), 1);
+    assert_eq!(pacer.ticks(10000.0, 4, 20.0), 4);
+    assert_eq!(pacer.ticks(10000.0, 40, 20.0), 1);
+    assert_eq!(Pacer::max_ticks(0), MAX_GENERATIONS_PER_FRAME);
+}
+
+#[test]
+fn test_pacer_does_not_catch_up_after_a_pause() {
+    let mut pacer = Pacer::default();
+    assert_eq!(pacer.ticks(10.0, 0, 60_000.0), 1);
+    assert_eq!(pacer.ticks(10000.0, 0, 60_000.0), MAX_GENERATIONS_PER_FRAME);
+    assert_eq!(pacer.ticks(10.0, 0, 0.0), 0);
+}
+
+#[test]
+fn test_describe_period() {
+    assert_eq!(describe_period(None), "Not Cycling");
+    assert_eq!(describe_period(Some(1)), "Still Life");
+    assert_eq!(describe_period(Some(3)), "Period 3");
+}
+
+#[test]
+fn test_population_chart() {
+    let mut history = PopulationHistory::default();
+    assert_eq!(history.points(), "");
+    history.push(10);
+    history.push(5);
+    history.push(0);
+    assert_eq!(history.peak(), 10);
+    assert_eq!(history.points(), "0.0,0.0 1.0,24.0 2.0,48.0");
+}
+
+#[test]
+fn test_population_history_drops_old_samples() {
+    let mut history = PopulationHistory::default();
+    for population in 0..SAMPLES as u64 + 50 {
+        history.push(population);
+    }
+    assert_eq!(history.samples.len(), SAMPLES);
+    assert_eq!(history.samples.front(), Some(&50));
+}
@@ -0,0 +1,247 @@
+use leptos::prelude::*;
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+
+use super::engine::rule::Rule;
+use super::engine::universe::Selection;
+use crate::color::{mix, rgb};
+
+/// Generations a cell must live to reach the old end of the heatmap.
+const HEAT_SPAN: f64 = 64.0;
+/// Generations a dead cell's trail takes to fade away.
+const TRAIL_LENGTH: i32 = 12;
+
+/// The colours cells are drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, EnumString, strum::Display)]
+pub enum Palette {
+    #[default]
+    Classic,
+    Dark,
+    Ocean,
+    Phosphor,
+}
+
+/// A palette's colours, as hex strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaletteColors {
+    pub dead: &'static str,
+    pub alive: &'static str,
+    /// Where dying cells of Generations rules start fading from.
+    pub dying: &'static str,
+    /// The ends of the age heatmap.
+    pub young: &'static str,
+    pub old: &'static str,
+    /// Where a trail starts fading from.
+    pub trail: &'static str,
+    pub grid: &'static str,
+}
+
+impl Palette {
+    pub fn colors(self) -> PaletteColors {
+        match self {
+            Palette::Classic => PaletteColors {
+                dead: "#FFFFFF",
+                alive: "#000000",
+                dying: "#D64541",
+                young: "#F9A825",
+                old: "#4A148C",
+                trail: "#9EC5FE",
+                grid: "#CCCCCC",
+            },
+            Palette::Dark => PaletteColors {
+                dead: "#111418",
+                alive: "#E8EAED",
+                dying: "#F2994A",
+                young: "#FFD54F",
+                old: "#E53935",
+                trail: "#2F3E5C",
+                grid: "#2A2F36",
+            },
+            Palette::Ocean => PaletteColors {
+                dead: "#0B2545",
+                alive: "#8DE4FF",
+                dying: "#3E7CB1",
+                young: "#E0FBFC",
+                old: "#1B98E0",
+                trail: "#134074",
+                grid: "#13315C",
+            },
+            Palette::Phosphor => PaletteColors {
+                dead: "#140E00",
+                alive: "#FFB000",
+                dying: "#9A5B00",
+                young: "#FFE08A",
+                old: "#B34700",
+                trail: "#4A3000",
+                grid: "#2B1F00",
+            },
+        }
+    }
+}
+
+/// How cells are coloured: the palette, and whether live cells are shaded by
+/// age and dead ones leave a fading trail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Theme {
+    pub palette: Palette,
+    pub heatmap: bool,
+    pub trails: bool,
+}
+
+/// RGBA colour for each of `states` states of a rule in `palette`: dying
+/// cells fade from the dying colour towards the dead one.
+pub fn state_colors(palette: Palette, states: u8) -> Vec<[u8; 4]> {
+    let colors = palette.colors();
+    let (dead, alive, dying) = (rgb(colors.dead), rgb(colors.alive), rgb(colors.dying));
+    (0..states)
+        .map(|state| {
+            let [r, g, b] = match state {
+                Rule::DEAD => dead,
+                Rule::ALIVE => alive,
+                _ => mix(dying, dead, (state - 2) as f64 / (states - 1) as f64),
+            };
+            [r, g, b, 255]
+        })
+        .collect()
+}
+
+/// Works out the colour of each cell from its state and age.
+pub struct Painter {
+    theme: Theme,
+    states: Vec<[u8; 4]>,
+    young: [u8; 3],
+    old: [u8; 3],
+    trail: [u8; 3],
+    dead: [u8; 3],
+}
+
+impl Painter {
+    pub fn new(theme: Theme, states: u8) -> Painter {
+        let colors = theme.palette.colors();
+        Painter {
+            theme,
+            states: state_colors(theme.palette, states),
+            young: rgb(colors.young),
+            old: rgb(colors.old),
+            trail: rgb(colors.trail),
+            dead: rgb(colors.dead),
+        }
+    }
+
+    /// The colour of a cell in `state` with `age` as kept by `Ages`.
+    pub fn color(&self, state: u8, age: i32) -> [u8; 4] {
+        let [r, g, b] = match state {
+            Rule::ALIVE if self.theme.heatmap && age > 0 => {
+                mix(self.young, self.old, (age as f64).ln() / HEAT_SPAN.ln())
+            }
+            Rule::DEAD if self.theme.trails && (-TRAIL_LENGTH..0).contains(&age) => mix(
+                self.trail,
+                self.dead,
+                (-age - 1) as f64 / TRAIL_LENGTH as f64,
+            ),
+            _ => return self.states[state as usize],
+        };
+        [r, g, b, 255]
+    }
+}
+
+/// How many generations each cell in view has been alive for, or, as a
+/// negative number, how long ago it died. Zero is a cell not seen alive.
+#[derive(Debug, Default)]
+pub struct Ages {
+    area: Selection,
+    ages: Vec<i32>,
+}
+
+impl Ages {
+    pub fn get(&self, idx: usize) -> i32 {
+        self.ages[idx]
+    }
+
+    /// Forgets every cell's age.
+    pub fn clear(&mut self) {
+        self.ages.fill(0);
+    }
+
+    /// Ages the cells of `area`, whose `states` are given row by row, by
+    /// `generations`. Cells that were in view before keep their ages when
+    /// the view moves.
+    pub fn update(&mut self, area: Selection, states: &[u8], generations: u32) {
+        if area != self.area {
+            self.move_to(area);
+        }
+        let generations = generations.min(i32::MAX as u32) as i32;
+        for (age, &state) in self.ages.iter_mut().zip(states) {
+            *age = match (state == Rule::ALIVE, *age) {
+                (true, age) if age > 0 => age.saturating_add(generations),
+                (true, _) => 1,
+                (false, 0) => 0,
+                (false, age) if age > 0 => -generations.max(1),
+                (false, age) => age.saturating_sub(generations),
+            };
+        }
+    }
+
+    fn move_to(&mut self, area: Selection) {
+        let mut ages = vec![0; (area.width * area.height) as usize];
+        for (idx, age) in ages.iter_mut().enumerate() {
+            let row = area.row + (idx / area.width as usize) as i64;
+            let column = area.column + (idx % area.width as usize) as i64;
+            let (old_row, old_column) = (row - self.area.row, column - self.area.column);
+            if (0..self.area.height as i64).contains(&old_row)
+                && (0..self.area.width as i64).contains(&old_column)
+            {
+                *age = self.ages[(old_row * self.area.width as i64 + old_column) as usize];
+            }
+        }
+        self.area = area;
+        self.ages = ages;
+    }
+}
+
+/// Picks the palette, and turns the age heatmap and trails on and off. The
+/// GPU backend keeps its cells on the GPU, so only its palette can change.
+#[component]
+pub fn ThemePicker(
+    #[prop(into)] theme: RwSignal<Theme>,
+    #[prop(into)] gpu: Signal<bool>,
+) -> impl IntoView {
+    view! {
+        <div class="life-theme">
+            <select
+                class="theme-select"
+                prop:value=move || theme.get().palette.to_string()
+                on:change=move |ev| {
+                    if let Ok(palette) = event_target_value(&ev).parse::<Palette>() {
+                        theme.update(|theme| theme.palette = palette);
+                    }
+                }
+            >
+                {Palette::iter()
+                    .map(|palette| view! { <option value=palette.to_string()>{palette.to_string()}</option> })
+                    .collect_view()}
+            </select>
+            <label class="theme-toggle">
+                <input
+                    type="checkbox"
+                    prop:checked=move || theme.get().heatmap
+                    prop:disabled=move || gpu.get()
+                    on:change=move |ev| theme.update(|theme| theme.heatmap = event_target_checked(&ev))
+                />
+                "Age Heatmap"
+            </label>
+            <label class="theme-toggle">
+                <input
+                    type="checkbox"
+                    prop:checked=move || theme.get().trails
+                    prop:disabled=move || gpu.get()
+                    on:change=move |ev| theme.update(|theme| theme.trails = event_target_checked(&ev))
+                />
+                "Trails"
+            </label>
+        </div>
+    }
+}
+
+#[cfg(test)]
+#[path = "theme_test.rs"]
+mod theme_test;
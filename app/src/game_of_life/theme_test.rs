@@ -0,0 +1,123 @@
+use super::*;
+
+fn area(row: i64, column: i64, width: u32, height: u32) -> Selection {
+    Selection {
+        row,
+        column,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn test_classic_palette_is_black_on_white() {
+    let colors = state_colors(Palette::Classic, 2);
+    assert_eq!(colors, vec![[255, 255, 255, 255], [0, 0, 0, 255]]);
+}
+
+#[test]
+fn test_dying_states_fade_towards_dead() {
+    let colors = state_colors(Palette::Classic, 4);
+    assert_eq!(colors[2], [0xD6, 0x45, 0x41, 255]);
+    assert!(colors[3][1] > colors[2][1]);
+    assert_ne!(colors[3], colors[0]);
+}
+
+#[test]
+fn test_every_palette_parses() {
+    for palette in Palette::iter() {
+        let colors = palette.colors();
+        for hex in [
+            colors.dead,
+            colors.alive,
+            colors.dying,
+            colors.young,
+            colors.old,
+            colors.trail,
+            colors.grid,
+        ] {
+            rgb(hex);
+        }
+    }
+}
+
+#[test]
+fn test_ages_count_generations_alive_and_dead() {
+    let mut ages = Ages::default();
+    let area = area(0, 0, 3, 1);
+    ages.update(area, &[Rule::ALIVE, Rule::DEAD, Rule::DEAD], 1);
+    assert_eq!(ages.ages, [1, 0, 0]);
+    ages.update(area, &[Rule::ALIVE, Rule::ALIVE, Rule::DEAD], 4);
+    assert_eq!(ages.ages, [5, 1, 0]);
+    ages.update(area, &[Rule::DEAD, Rule::ALIVE, Rule::DEAD], 1);
+    assert_eq!(ages.ages, [-1, 2, 0]);
+    ages.update(area, &[Rule::DEAD, Rule::DEAD, Rule::DEAD], 3);
+    assert_eq!(ages.ages, [-4, -3, 0]);
+    // Redrawing without running keeps the ages.
+    ages.update(area, &[Rule::DEAD, Rule::DEAD, Rule::DEAD], 0);
+    assert_eq!(ages.ages, [-4, -3, 0]);
+    ages.clear();
+    assert_eq!(ages.ages, [0, 0, 0]);
+}
+
+#[test]
+fn test_ages_follow_cells_as_the_view_moves() {
+    let mut ages = Ages::default();
+    ages.update(area(0, 0, 2, 2), &[Rule::ALIVE; 4], 1);
+    ages.update(area(0, 0, 2, 2), &[Rule::ALIVE; 4], 1);
+    // Moved a column right and grown by a row: the left column has gone.
+    ages.update(
+        area(0, 1, 2, 3),
+        &[
+            Rule::ALIVE,
+            Rule::DEAD,
+            Rule::ALIVE,
+            Rule::DEAD,
+            Rule::DEAD,
+            Rule::DEAD,
+        ],
+        1,
+    );
+    assert_eq!(ages.ages, [3, 0, 3, 0, 0, 0]);
+}
+
+#[test]
+fn test_plain_theme_colours_by_state() {
+    let painter = Painter::new(Theme::default(), 2);
+    assert_eq!(painter.color(Rule::ALIVE, 100), [0, 0, 0, 255]);
+    assert_eq!(painter.color(Rule::DEAD, -1), [255, 255, 255, 255]);
+}
+
+#[test]
+fn test_heatmap_runs_from_young_to_old() {
+    let theme = Theme {
+        heatmap: true,
+        ..Theme::default()
+    };
+    let painter = Painter::new(theme, 2);
+    let colors = Palette::Classic.colors();
+    let [r, g, b] = rgb(colors.young);
+    assert_eq!(painter.color(Rule::ALIVE, 1), [r, g, b, 255]);
+    let [r, g, b] = rgb(colors.old);
+    assert_eq!(painter.color(Rule::ALIVE, 64), [r, g, b, 255]);
+    assert_eq!(painter.color(Rule::ALIVE, 10_000), [r, g, b, 255]);
+}
+
+#[test]
+fn test_trails_fade_out() {
+    let theme = Theme {
+        trails: true,
+        ..Theme::default()
+    };
+    let painter = Painter::new(theme, 2);
+    let [r, g, b] = rgb(Palette::Classic.colors().trail);
+    assert_eq!(painter.color(Rule::DEAD, -1), [r, g, b, 255]);
+    let fading = painter.color(Rule::DEAD, -6);
+    assert!(fading[0] > r && fading[0] < 255);
+    assert_eq!(
+        painter.color(Rule::DEAD, -TRAIL_LENGTH - 1),
+        [255, 255, 255, 255]
+    );
+    // Cells never seen alive leave no trail.
+    assert_eq!(painter.color(Rule::DEAD, 0), [255, 255, 255, 255]);
+}
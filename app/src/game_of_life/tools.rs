@@ -0,0 +1,83 @@
+use strum::{EnumIter, EnumString};
+
+use super::engine::pattern::Pattern;
+use super::engine::rule::Rule;
+
+/// What dragging across the canvas does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, EnumString, strum::Display)]
+pub enum Tool {
+    /// Paints live cells, or erases when the stroke starts on a live cell.
+    #[default]
+    Draw,
+    Erase,
+    Line,
+    Rectangle,
+    Select,
+}
+
+impl Tool {
+    /// The cells a shape tool would draw when dragged between two cells.
+    pub fn shape(self, from: (i64, i64), to: (i64, i64)) -> Option<Vec<(i64, i64)>> {
+        match self {
+            Tool::Line => Some(line(from, to)),
+            Tool::Rectangle => Some(rectangle(from, to)),
+            Tool::Draw | Tool::Erase | Tool::Select => None,
+        }
+    }
+}
+
+/// The cells on a straight line between two cells, both included, using
+/// Bresenham's algorithm.
+pub fn line(from: (i64, i64), to: (i64, i64)) -> Vec<(i64, i64)> {
+    let (mut row, mut column) = from;
+    let (delta_row, delta_col) = ((to.0 - row).abs(), -(to.1 - column).abs());
+    let (step_row, step_col) = ((to.0 - row).signum(), (to.1 - column).signum());
+    let mut error = delta_row + delta_col;
+    let mut cells = vec![(row, column)];
+    while (row, column) != to {
+        let doubled = 2 * error;
+        if doubled >= delta_col {
+            error += delta_col;
+            row += step_row;
+        }
+        if doubled <= delta_row {
+            error += delta_row;
+            column += step_col;
+        }
+        cells.push((row, column));
+    }
+    cells
+}
+
+/// The outline of the rectangle with opposite corners at two cells.
+pub fn rectangle(from: (i64, i64), to: (i64, i64)) -> Vec<(i64, i64)> {
+    let (top, bottom) = (from.0.min(to.0), from.0.max(to.0));
+    let (left, right) = (from.1.min(to.1), from.1.max(to.1));
+    let mut cells = Vec::new();
+    for row in top..=bottom {
+        for column in left..=right {
+            if row == top || row == bottom || column == left || column == right {
+                cells.push((row, column));
+            }
+        }
+    }
+    cells
+}
+
+/// A pattern of live `cells`, with the cell its top left corner goes at.
+pub fn to_pattern(cells: &[(i64, i64)]) -> (Pattern, i64, i64) {
+    let top = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, column)| column).min().unwrap_or(0);
+    let bottom = cells.iter().map(|&(row, _)| row).max().unwrap_or(0);
+    let right = cells.iter().map(|&(_, column)| column).max().unwrap_or(0);
+    let (width, height) = ((right - left + 1) as u32, (bottom - top + 1) as u32);
+    let mut pattern = Pattern::new(width, height);
+    for &(row, column) in cells {
+        pattern.cells[((row - top) as u32 * width + (column - left) as u32) as usize] = Rule::ALIVE;
+    }
+    (pattern, top, left)
+}
+
+#[cfg(test)]
+#[path = "tools_test.rs"]
+mod tools_test;
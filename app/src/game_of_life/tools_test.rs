@@ -0,0 +1,61 @@
+use super::*;
+
+#[test]
+fn test_line() {
+    assert_eq!(line((2, 2), (2, 2)), vec![(2, 2)]);
+    assert_eq!(line((0, 0), (0, 3)), vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+    assert_eq!(line((3, 3), (0, 0)), vec![(3, 3), (2, 2), (1, 1), (0, 0)]);
+    assert_eq!(
+        line((0, 0), (1, 4)),
+        vec![(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)]
+    );
+}
+
+#[test]
+fn test_line_has_no_gaps() {
+    for to in [(7, -3), (-5, 9), (12, 1), (-2, -11)] {
+        let cells = line((0, 0), to);
+        assert_eq!(cells.last(), Some(&to));
+        for pair in cells.windows(2) {
+            let ((a_row, a_col), (b_row, b_col)) = (pair[0], pair[1]);
+            assert!(a_row.abs_diff(b_row) <= 1 && a_col.abs_diff(b_col) <= 1);
+        }
+    }
+}
+
+#[test]
+fn test_rectangle() {
+    let mut cells = rectangle((2, 3), (0, 0));
+    cells.sort();
+    assert_eq!(
+        cells,
+        vec![
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 0),
+            (1, 3),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (2, 3),
+        ]
+    );
+    assert_eq!(rectangle((-1, -1), (-1, -1)), vec![(-1, -1)]);
+}
+
+#[test]
+fn test_to_pattern() {
+    let (pattern, top, left) = to_pattern(&line((-1, 5), (1, 3)));
+    assert_eq!((top, left), (-1, 3));
+    assert_eq!(pattern.to_rle(), "x = 3, y = 3\n2bo$bo$o!\n");
+}
+
+#[test]
+fn test_shapes_by_tool() {
+    assert_eq!(Tool::Line.shape((0, 0), (0, 1)), Some(vec![(0, 0), (0, 1)]));
+    assert!(Tool::Rectangle.shape((0, 0), (2, 2)).is_some());
+    assert_eq!(Tool::Draw.shape((0, 0), (2, 2)), None);
+    assert_eq!(Tool::Select.shape((0, 0), (2, 2)), None);
+}
@@ -0,0 +1,69 @@
+use super::engine::universe::Selection;
+
+/// Which part of the universe the canvas shows, and how big the cells are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The cell coordinates at the top left corner of the canvas. Fractional
+    /// when the view is part way across a cell.
+    pub top: f64,
+    pub left: f64,
+    /// Canvas pixels per cell.
+    pub zoom: f64,
+}
+
+impl Viewport {
+    pub const MIN_ZOOM: f64 = 1.0;
+    pub const MAX_ZOOM: f64 = 64.0;
+    /// Grid lines are drawn once cells are at least this many pixels wide.
+    pub const GRID_ZOOM: f64 = 6.0;
+
+    /// A view of the cells from the origin, `zoom` pixels apiece.
+    pub fn new(zoom: f64) -> Viewport {
+        Viewport {
+            top: 0.0,
+            left: 0.0,
+            zoom: zoom.clamp(Viewport::MIN_ZOOM, Viewport::MAX_ZOOM),
+        }
+    }
+
+    /// The cell under a point on the canvas, in canvas pixels.
+    pub fn cell_at(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (self.top + y / self.zoom).floor() as i64,
+            (self.left + x / self.zoom).floor() as i64,
+        )
+    }
+
+    /// Scales the cells by `factor`, keeping the point under (`x`, `y`) still.
+    pub fn zoom_at(&mut self, x: f64, y: f64, factor: f64) {
+        let (row, column) = (self.top + y / self.zoom, self.left + x / self.zoom);
+        self.zoom = (self.zoom * factor).clamp(Viewport::MIN_ZOOM, Viewport::MAX_ZOOM);
+        self.top = row - y / self.zoom;
+        self.left = column - x / self.zoom;
+    }
+
+    /// Drags the view by a distance in canvas pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.left -= dx / self.zoom;
+        self.top -= dy / self.zoom;
+    }
+
+    /// Every cell at least partly on a canvas of the given size.
+    pub fn visible(&self, width: u32, height: u32) -> Selection {
+        let (row, column) = (self.top.floor(), self.left.floor());
+        Selection {
+            row: row as i64,
+            column: column as i64,
+            width: ((self.left + width as f64 / self.zoom).ceil() - column) as u32,
+            height: ((self.top + height as f64 / self.zoom).ceil() - row) as u32,
+        }
+    }
+
+    pub fn shows_grid(&self) -> bool {
+        self.zoom >= Viewport::GRID_ZOOM
+    }
+}
+
+#[cfg(test)]
+#[path = "viewport_test.rs"]
+mod viewport_test;
@@ -0,0 +1,58 @@
+use super::*;
+
+#[test]
+fn test_cell_at() {
+    let mut view = Viewport::new(8.0);
+    assert_eq!(view.cell_at(0.0, 0.0), (0, 0));
+    assert_eq!(view.cell_at(17.0, 9.0), (1, 2));
+    view.pan(4.0, 4.0);
+    assert_eq!(view.cell_at(0.0, 0.0), (-1, -1));
+    assert_eq!(view.cell_at(4.0, 4.0), (0, 0));
+}
+
+#[test]
+fn test_zoom_keeps_the_cursor_still() {
+    let mut view = Viewport::new(8.0);
+    let before = view.cell_at(100.0, 60.0);
+    view.zoom_at(100.0, 60.0, 2.0);
+    assert_eq!(view.zoom, 16.0);
+    assert_eq!(view.cell_at(100.0, 60.0), before);
+    view.zoom_at(100.0, 60.0, 0.25);
+    assert_eq!(view.zoom, 4.0);
+    assert_eq!(view.cell_at(100.0, 60.0), before);
+}
+
+#[test]
+fn test_zoom_is_clamped() {
+    let mut view = Viewport::new(8.0);
+    view.zoom_at(0.0, 0.0, 1000.0);
+    assert_eq!(view.zoom, Viewport::MAX_ZOOM);
+    view.zoom_at(0.0, 0.0, 0.0001);
+    assert_eq!(view.zoom, Viewport::MIN_ZOOM);
+    assert!(!view.shows_grid());
+}
+
+#[test]
+fn test_visible_cells() {
+    let mut view = Viewport::new(8.0);
+    assert_eq!(
+        view.visible(80, 40),
+        Selection {
+            row: 0,
+            column: 0,
+            width: 10,
+            height: 5,
+        }
+    );
+    // Part way across a cell, both the first and the last are partly shown.
+    view.pan(-4.0, 12.0);
+    assert_eq!(
+        view.visible(80, 40),
+        Selection {
+            row: -2,
+            column: 0,
+            width: 11,
+            height: 6,
+        }
+    );
+}
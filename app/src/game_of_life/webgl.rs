@@ -1,35 +1,63 @@
-use super::universe::DomBounds;
-use crate::game_of_life::universe::Universe;
-use crate::game_of_life::universe::UniverseRenderer;
-use crate::mandelbrot::Bounds;
+use super::engine::pattern::Pattern;
+use super::engine::rule::Rule;
+use super::engine::universe::{Selection, UniverseBackend};
+use super::gpu::GpuLife;
+use super::theme::{state_colors, Ages, Painter, Theme};
+use super::viewport::Viewport;
+use crate::color::rgb;
 use crate::point2d::Point2D;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlCanvasElement, WebGlProgram, WebGlRenderingContext as GL, WebGlShader};
+use web_sys::{
+    HtmlCanvasElement, WebGlProgram, WebGlRenderingContext as GL, WebGlShader, WebGlTexture,
+};
+
+pub(crate) struct DomBounds {
+    pub origin: Point2D<f64>,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub(crate) trait UniverseRenderer {
+    fn render(&mut self, universe: &dyn UniverseBackend) -> Result<(), ()>;
+    /// The cell under a point on the page, as (row, column).
+    fn get_cell_index(&self, bounding_rect: DomBounds, p: Point2D<i32>) -> (i64, i64);
+}
 
 pub struct WebGLRenderer {
     canvas: HtmlCanvasElement,
     program: WebGlProgram,
-    width: u32,
-    height: u32,
+    /// The view the canvas starts with, showing the whole grid.
+    home: Viewport,
+    view: Viewport,
+    /// The cells the texture holds, which are the ones in view.
+    visible: Selection,
     texture: Vec<u8>,
+    image: Option<WebGlTexture>,
+    /// Whether the texture holds only the preview, for drawing over a GPU
+    /// universe, rather than the cells too.
+    overlay: bool,
+    /// Colours for each state of a GPU universe, and how many states.
+    palette: Option<WebGlTexture>,
+    palette_states: u8,
+    theme: Theme,
+    /// Ages of the cells in view, and the generations run since they were
+    /// last brought up to date.
+    ages: Ages,
+    generations: u32,
     gl: GL,
     buffer: web_sys::WebGlBuffer,
+    selection: Option<Selection>,
+    /// A pattern about to be stamped, with the cell under the cursor.
+    preview: Option<(Pattern, i64, i64)>,
 }
 
 impl UniverseRenderer for WebGLRenderer {
-    fn get_cell_index(bounding_rect: DomBounds, bounds: Bounds, p: Point2D<i32>) -> (u32, u32) {
-        let scale_x = bounds.width as f64 / bounding_rect.width;
-        let scale_y = bounds.height as f64 / bounding_rect.height;
-
-        let canvas_left = (p.x as f64 - bounding_rect.origin.x) * scale_x;
-        let canvas_top = (p.y as f64 - bounding_rect.origin.y) * scale_y;
-
-        let row = canvas_top.floor().min((bounds.height - 1) as f64);
-        let col = canvas_left.floor().min((bounds.width - 1) as f64);
-        (bounds.height - 1 - row as u32, col as u32)
+    fn get_cell_index(&self, bounding_rect: DomBounds, p: Point2D<i32>) -> (i64, i64) {
+        let (x, y) = self.canvas_point(&bounding_rect, p);
+        self.view.cell_at(x, y)
     }
 
-    fn render(&mut self, universe: &Universe) -> Result<(), ()> {
+    fn render(&mut self, universe: &dyn UniverseBackend) -> Result<(), ()> {
         let gl = self.gl.clone();
 
         if gl.is_context_lost() {
@@ -44,11 +72,72 @@ impl UniverseRenderer for WebGLRenderer {
             self.canvas.height() as i32,
         );
 
-        self.update_texture(universe);
+        // A GPU universe may have bound its own textures since last time.
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, self.image.as_ref());
+        match GpuLife::of(universe) {
+            Some(life) => {
+                // The cells stay on the GPU, so their ages aren't followed.
+                self.generations = 0;
+                self.update_overlay();
+                self.update_palette(universe.rule().states());
+                gl.active_texture(GL::TEXTURE1);
+                gl.bind_texture(GL::TEXTURE_2D, Some(life.texture()));
+                gl.active_texture(GL::TEXTURE2);
+                gl.bind_texture(GL::TEXTURE_2D, self.palette.as_ref());
+                gl.active_texture(GL::TEXTURE0);
+            }
+            None => self.update_texture(universe),
+        }
 
         gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffer));
 
         gl.use_program(Some(&self.program));
+        let visible = self.visible;
+        let indexed_loc = gl.get_uniform_location(&self.program, "indexed");
+        gl.uniform1i(indexed_loc.as_ref(), GpuLife::of(universe).is_some() as i32);
+        if let Some(life) = GpuLife::of(universe) {
+            let (width, height) = life.size();
+            let origin_loc = gl.get_uniform_location(&self.program, "origin");
+            gl.uniform2f(
+                origin_loc.as_ref(),
+                visible.column.rem_euclid(width as i64) as f32,
+                visible.row.rem_euclid(height as i64) as f32,
+            );
+            let size_loc = gl.get_uniform_location(&self.program, "universeSize");
+            gl.uniform2f(size_loc.as_ref(), width as f32, height as f32);
+            let states_loc = gl.get_uniform_location(&self.program, "states");
+            gl.uniform1f(states_loc.as_ref(), self.palette_states as f32);
+        }
+        // Everything the shader sees is relative to the texture, keeping the
+        // numbers small however far the view has been panned.
+        let offset = [
+            (self.view.left - visible.column as f64) as f32,
+            (self.view.top - visible.row as f64) as f32,
+        ];
+        let offset_loc = gl.get_uniform_location(&self.program, "offset");
+        gl.uniform2fv_with_f32_array(offset_loc.as_ref(), &offset);
+        let zoom_loc = gl.get_uniform_location(&self.program, "zoom");
+        gl.uniform1f(zoom_loc.as_ref(), self.view.zoom as f32);
+        let cells_loc = gl.get_uniform_location(&self.program, "cells");
+        gl.uniform2fv_with_f32_array(
+            cells_loc.as_ref(),
+            &[visible.width as f32, visible.height as f32],
+        );
+        let grid_loc = gl.get_uniform_location(&self.program, "grid");
+        gl.uniform1i(grid_loc.as_ref(), self.view.shows_grid() as i32);
+
+        let selection = match self.selection {
+            Some(s) => [
+                (s.column - visible.column) as f32,
+                (s.row - visible.row) as f32,
+                (s.column - visible.column + s.width as i64 - 1) as f32,
+                (s.row - visible.row + s.height as i64 - 1) as f32,
+            ],
+            None => [-1.0, -1.0, -2.0, -2.0],
+        };
+        let selection_loc = gl.get_uniform_location(&self.program, "selection");
+        gl.uniform4fv_with_f32_array(selection_loc.as_ref(), &selection);
 
         gl.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, 0, 0);
         gl.enable_vertex_attrib_array(0);
@@ -62,68 +151,250 @@ impl UniverseRenderer for WebGLRenderer {
     }
 }
 
-fn update_universe_image<'a>(image: &'a mut Vec<u8>, universe: &'_ &Universe) -> &'a [u8] {
-    for (i, cell) in image.iter_mut().enumerate() {
-        if universe.is_alive(i) {
-            *cell = 0;
-        } else {
-            *cell = 255;
+/// Draws the cells in `visible` in `theme`, after ageing them by
+/// `generations`.
+fn update_universe_image(
+    image: &mut [u8],
+    visible: Selection,
+    universe: &dyn UniverseBackend,
+    theme: Theme,
+    ages: &mut Ages,
+    generations: u32,
+) {
+    let width = visible.width as usize;
+    let states: Vec<u8> = (0..width * visible.height as usize)
+        .map(|i| {
+            universe.state(
+                visible.row + (i / width) as i64,
+                visible.column + (i % width) as i64,
+            )
+        })
+        .collect();
+    ages.update(visible, &states, generations);
+    let painter = Painter::new(theme, universe.rule().states());
+    for (i, (pixel, &state)) in image.chunks_exact_mut(4).zip(&states).enumerate() {
+        pixel.copy_from_slice(&painter.color(state, ages.get(i)));
+    }
+}
+
+/// Colour a pending pattern's live cells are tinted with.
+const GHOST: [u8; 3] = [0x33, 0x66, 0xFF];
+
+/// Where in an image of the cells in `visible` a pending pattern would bring
+/// cells to life.
+fn preview_pixels(
+    visible: Selection,
+    preview: &(Pattern, i64, i64),
+) -> impl Iterator<Item = usize> + '_ {
+    let (pattern, row, column) = preview;
+    (0..pattern.height)
+        .flat_map(move |pattern_row| {
+            (0..pattern.width).map(move |pattern_col| (pattern_row, pattern_col))
+        })
+        .filter(|&(pattern_row, pattern_col)| pattern.get(pattern_row, pattern_col) != Rule::DEAD)
+        .filter_map(move |(pattern_row, pattern_col)| {
+            let image_row = row + pattern_row as i64 - visible.row;
+            let image_col = column + pattern_col as i64 - visible.column;
+            ((0..visible.height as i64).contains(&image_row)
+                && (0..visible.width as i64).contains(&image_col))
+            .then(|| (image_row * visible.width as i64 + image_col) as usize)
+        })
+}
+
+/// Tints the cells in `visible` a pending pattern would bring to life.
+fn draw_preview(image: &mut [u8], visible: Selection, preview: &(Pattern, i64, i64)) {
+    for idx in preview_pixels(visible, preview) {
+        for (channel, ghost) in image[idx * 4..idx * 4 + 3].iter_mut().zip(GHOST) {
+            *channel = ((*channel as u16 + ghost as u16) / 2) as u8;
         }
     }
-    image.as_slice()
 }
 
 impl WebGLRenderer {
     const CELL_SIZE: u32 = 8;
-    const _GRID_COLOR: &'static str = "#CCCCCC";
-    const _DEAD_COLOR: &'static str = "#FFFFFF";
-    const _ALIVE_COLOR: &'static str = "#000000";
-
-    pub fn update_texture(&mut self, universe: &Universe) {
-        let level = 0;
-        let src_format = GL::LUMINANCE;
-        let src_type = GL::UNSIGNED_BYTE;
-        let width = self.width;
-        let height = self.height;
-        let pixel = update_universe_image(&mut self.texture, &universe);
-        assert!(pixel.len() == (width * height) as usize);
+    /// Big grids get smaller cells to keep the canvas within this many pixels
+    /// a side.
+    const MAX_CANVAS_SIDE: u32 = 4096;
+
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
+    }
+
+    /// Draws the cells in `theme` from the next render on.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        // Upload the GPU palette again.
+        self.palette_states = 0;
+        self.gl.use_program(Some(&self.program));
+        let grid_color_loc = self.gl.get_uniform_location(&self.program, "gridColor");
+        self.gl.uniform3fv_with_f32_array(
+            grid_color_loc.as_ref(),
+            &rgb(theme.palette.colors().grid).map(|c| c as f32 / 255.0),
+        );
+    }
+
+    /// Notes that the universe has run on `generations` generations, which
+    /// the cells in view age by when they are next drawn.
+    pub fn age(&mut self, generations: u64) {
+        self.generations = self
+            .generations
+            .saturating_add(generations.min(u64::from(u32::MAX)) as u32);
+    }
+
+    /// Forgets how old the cells are, such as when the universe is refilled.
+    pub fn clear_ages(&mut self) {
+        self.ages.clear();
+        self.generations = 0;
+    }
+
+    pub fn set_preview(&mut self, preview: Option<(Pattern, i64, i64)>) {
+        self.preview = preview;
+        self.overlay = false;
+    }
+
+    /// The context the canvas is drawn with, which GPU universes simulate
+    /// in.
+    pub fn context(&self) -> GL {
+        self.gl.clone()
+    }
+
+    pub fn view_mut(&mut self) -> &mut Viewport {
+        &mut self.view
+    }
+
+    pub fn view(&self) -> Viewport {
+        self.view
+    }
+
+    /// Goes back to showing the whole grid.
+    pub fn reset_view(&mut self) {
+        self.view = self.home;
+    }
+
+    /// A point on the page in canvas pixels, which differ from CSS pixels
+    /// once the canvas is scaled to fit the page.
+    pub fn canvas_point(&self, bounding_rect: &DomBounds, p: Point2D<i32>) -> (f64, f64) {
+        let scale_x = self.canvas.width() as f64 / bounding_rect.width;
+        let scale_y = self.canvas.height() as f64 / bounding_rect.height;
+        (
+            (p.x as f64 - bounding_rect.origin.x) * scale_x,
+            (p.y as f64 - bounding_rect.origin.y) * scale_y,
+        )
+    }
+
+    pub fn update_texture(&mut self, universe: &dyn UniverseBackend) {
+        let visible = self.view.visible(self.canvas.width(), self.canvas.height());
+        let (width, height) = (visible.width, visible.height);
+        let resized = (width, height) != (self.visible.width, self.visible.height);
+        self.visible = visible;
+        self.texture.resize((width * height * 4) as usize, 255);
+
+        update_universe_image(
+            &mut self.texture,
+            visible,
+            universe,
+            self.theme,
+            &mut self.ages,
+            self.generations,
+        );
+        self.generations = 0;
+        if let Some(preview) = &self.preview {
+            draw_preview(&mut self.texture, visible, preview);
+        }
+        self.overlay = false;
+        self.upload_texture(resized);
+    }
+
+    /// Draws the preview alone, transparent elsewhere, for laying over a GPU
+    /// universe. It is only redrawn when the view or the preview changes.
+    fn update_overlay(&mut self) {
+        let visible = self.view.visible(self.canvas.width(), self.canvas.height());
+        if self.overlay && visible == self.visible {
+            return;
+        }
+        let (width, height) = (visible.width, visible.height);
+        let resized = (width, height) != (self.visible.width, self.visible.height);
+        self.visible = visible;
+        self.texture.clear();
+        self.texture.resize((width * height * 4) as usize, 0);
+        if let Some(preview) = &self.preview {
+            for idx in preview_pixels(visible, preview) {
+                let [r, g, b] = GHOST;
+                self.texture[idx * 4..idx * 4 + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+        self.overlay = true;
+        self.upload_texture(resized);
+    }
+
+    /// Uploads the colours for each of `states` states when they change.
+    fn update_palette(&mut self, states: u8) {
+        if states == self.palette_states {
+            return;
+        }
+        self.palette_states = states;
+        let colors = state_colors(self.theme.palette, states).concat();
+        self.gl.active_texture(GL::TEXTURE2);
+        self.gl.bind_texture(GL::TEXTURE_2D, self.palette.as_ref());
         self.gl
-            .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
                 GL::TEXTURE_2D,
-                level,
-                0, // xoffset
-                0, // yoffset
-                width as i32,
-                height as i32,
-                src_format,
-                src_type,
-                Some(pixel),
+                0,
+                GL::RGBA as i32,
+                states as i32,
+                1,
+                0,
+                GL::RGBA,
+                GL::UNSIGNED_BYTE,
+                Some(&colors),
             )
             .ok();
+        self.gl.active_texture(GL::TEXTURE0);
+    }
 
-        if (self.width & (self.width - 1)) == 0 && (self.height & (self.height - 1)) == 0 {
-            self.gl.generate_mipmap(GL::TEXTURE_2D);
-        } else {
-            self.gl
-                .tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+    /// Sends the image to the texture, reallocating it if its size changed.
+    fn upload_texture(&self, resized: bool) {
+        let (width, height) = (self.visible.width, self.visible.height);
+        let pixel = self.texture.as_slice();
+        if resized {
             self.gl
-                .tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+                .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                    GL::TEXTURE_2D,
+                    0,               // level
+                    GL::RGBA as i32, // internal format
+                    width as i32,
+                    height as i32,
+                    0,                 // border
+                    GL::RGBA,          // src format
+                    GL::UNSIGNED_BYTE, // src type
+                    Some(pixel),
+                )
+                .ok();
+        } else {
             self.gl
-                .tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+                .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_u8_array(
+                    GL::TEXTURE_2D,
+                    0, // level
+                    0, // xoffset
+                    0, // yoffset
+                    width as i32,
+                    height as i32,
+                    GL::RGBA,
+                    GL::UNSIGNED_BYTE,
+                    Some(pixel),
+                )
+                .ok();
         }
-
-        // Change magnification filter to nearest neighbor to prevent fuzzies
-        self.gl
-            .tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
     }
 }
 
 impl WebGLRenderer {
     pub fn new(canvas: HtmlCanvasElement, width: u32, height: u32) -> WebGLRenderer {
-        canvas.set_height((WebGLRenderer::CELL_SIZE + 1) * height + 1);
-        canvas.set_width((WebGLRenderer::CELL_SIZE + 1) * width + 1);
-
-        let size = (width * height) as usize;
+        let pitch = (WebGLRenderer::MAX_CANVAS_SIDE / width.max(height))
+            .clamp(1, WebGLRenderer::CELL_SIZE + 1);
+        canvas.set_height(pitch * height + 1);
+        canvas.set_width(pitch * width + 1);
+        let home = Viewport::new(pitch as f64);
 
         let gl = canvas
             .get_context("webgl")
@@ -140,17 +411,6 @@ impl WebGLRenderer {
 
         let program = link_program(&gl, &vert_shader, &frag_shader).unwrap();
         gl.use_program(Some(&program));
-        let pitch_loc = gl.get_uniform_location(&program, "pitch");
-        gl.uniform2fv_with_f32_array(
-            pitch_loc.as_ref(),
-            &[
-                (WebGLRenderer::CELL_SIZE + 1) as f32,
-                (WebGLRenderer::CELL_SIZE + 1) as f32,
-            ],
-        );
-
-        let vpw_loc = gl.get_uniform_location(&program, "vpw");
-        gl.uniform1f(vpw_loc.as_ref(), canvas.width() as f32);
         let vph_loc = gl.get_uniform_location(&program, "vph");
         gl.uniform1f(vph_loc.as_ref(), canvas.height() as f32);
         let usampler_loc = gl.get_uniform_location(&program, "uSampler");
@@ -161,24 +421,27 @@ impl WebGLRenderer {
         // Tell the shader we bound the texture to texture unit 0
         gl.uniform1i(usampler_loc.as_ref(), 0);
 
+        // GPU universes' cells and their colours go in units 1 and 2.
+        let states_loc = gl.get_uniform_location(&program, "uStates");
+        gl.uniform1i(states_loc.as_ref(), 1);
+        let palette_loc = gl.get_uniform_location(&program, "palette");
+        gl.uniform1i(palette_loc.as_ref(), 2);
+
+        let create_texture = || {
+            let texture = gl.create_texture();
+            gl.bind_texture(GL::TEXTURE_2D, texture.as_ref());
+            // The texture is resized to fit the view as it changes, so its
+            // sides are seldom powers of two.
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+            // Nearest neighbour magnification keeps the cells sharp.
+            gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+            texture
+        };
+        let palette = create_texture();
         // Create and bind the texture to texture unit 0
-        let webgl_texture = gl.create_texture();
-        gl.bind_texture(GL::TEXTURE_2D, webgl_texture.as_ref());
-
-        // Allocate texture memory
-        let initial_pixels = vec![255u8; size]; // Start with all dead cells (white)
-        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
-            GL::TEXTURE_2D,
-            0,                    // level
-            GL::LUMINANCE as i32, // internal format
-            width as i32,
-            height as i32,
-            0,                 // border
-            GL::LUMINANCE,     // src format
-            GL::UNSIGNED_BYTE, // src type
-            Some(&initial_pixels),
-        )
-        .ok();
+        let image = create_texture();
 
         // Create and upload vertex buffer once
         let vertices: [f32; 12] = [
@@ -196,15 +459,32 @@ impl WebGLRenderer {
             gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &vert_array, GL::STATIC_DRAW);
         }
 
-        WebGLRenderer {
+        let mut renderer = WebGLRenderer {
             canvas,
             program,
-            width,
-            height,
-            texture: vec![255; size],
+            home,
+            view: home,
+            visible: Selection {
+                row: 0,
+                column: 0,
+                width: 0,
+                height: 0,
+            },
+            texture: Vec::new(),
+            image,
+            overlay: false,
+            palette,
+            palette_states: 0,
+            theme: Theme::default(),
+            ages: Ages::default(),
+            generations: 0,
             gl,
             buffer,
-        }
+            selection: None,
+            preview: None,
+        };
+        renderer.set_theme(Theme::default());
+        renderer
     }
 }
 
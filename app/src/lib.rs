@@ -1,4 +1,5 @@
 #![feature(duration_millis_float)]
+#![cfg_attr(test, feature(test))]
 #![recursion_limit = "512"]
 use analytics::AnalyticsComponent;
 use catscii::Catscii;
@@ -18,10 +19,10 @@ mod analytics;
 mod catscii;
 pub mod chip8;
 mod color;
-mod game_of_life;
+pub mod game_of_life;
 mod ishihara;
 mod ishihara_form;
-mod mandelbrot;
+pub mod mandelbrot;
 mod point2d;
 
 #[cfg(feature = "ssr")]
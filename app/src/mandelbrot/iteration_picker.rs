@@ -0,0 +1,49 @@
+use leptos::prelude::*;
+
+use super::render::{Iterations, MIN_ITERATIONS};
+
+/// Sets how many steps orbits are followed for, up to `max`, or leaves it to
+/// grow with `zoom`.
+#[component]
+pub fn IterationPicker(
+    #[prop(into)] iterations: RwSignal<Iterations>,
+    #[prop(into)] zoom: Signal<f64>,
+    #[prop(into)] max: Signal<u32>,
+) -> impl IntoView {
+    let auto = move || iterations.get() == Iterations::Auto;
+
+    view! {
+        <div class="mandelbrot-iterations">
+            <label class="mandelbrot-toggle">
+                <input
+                    type="checkbox"
+                    prop:checked=auto
+                    on:change=move |ev| {
+                        iterations.set(if event_target_checked(&ev) {
+                            Iterations::Auto
+                        } else {
+                            // Keep the limit the zoom had reached.
+                            Iterations::Fixed(iterations.get_untracked().limit(zoom.get_untracked(), max.get_untracked()))
+                        });
+                    }
+                />
+                "Auto"
+            </label>
+            <label class="mandelbrot-limit">
+                "Max Iterations: "
+                <input
+                    type="number"
+                    min=MIN_ITERATIONS
+                    max=max
+                    prop:value=move || iterations.get().limit(zoom.get(), max.get())
+                    prop:disabled=auto
+                    on:change=move |ev| {
+                        if let Ok(limit) = event_target_value(&ev).parse::<u32>() {
+                            iterations.set(Iterations::Fixed(limit.clamp(MIN_ITERATIONS, max.get_untracked())));
+                        }
+                    }
+                />
+            </label>
+        </div>
+    }
+}
@@ -0,0 +1,394 @@
+mod iteration_picker;
+mod palette;
+pub mod remote;
+mod render;
+mod view;
+
+use anyhow::anyhow;
+use image::RgbaImage;
+use iteration_picker::IterationPicker;
+use leptos::ev;
+use leptos::html::Canvas;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::params::ParamsMap;
+use leptos_router::NavigateOptions;
+use leptos_use::{use_event_listener_with_options, UseEventListenerOptions};
+use palette::{Coloring, ColoringPicker};
+use remote::{Encoding, RenderMode, RenderRequest};
+pub use render::Bounds;
+use render::{generate_mandelbrot, Iterations};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use view::{View, ViewHistory};
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent};
+
+/// How much one notch of the mouse wheel zooms by.
+const ZOOM_STEP: f64 = 1.25;
+/// How much a click zooms in, or out with Shift held, by.
+const CLICK_ZOOM: f64 = 2.0;
+/// Drags shorter than this, in canvas pixels, are clicks.
+const CLICK_DISTANCE: f64 = 4.0;
+/// Wheel notches closer together than this, in milliseconds, go back as one.
+const WHEEL_GESTURE_MS: f64 = 500.0;
+/// How long the view has to settle before it is rendered, so a run of wheel
+/// notches or quick changes renders once rather than for each.
+const RENDER_DELAY: Duration = Duration::from_millis(150);
+
+/// Puts `image` on `canvas`, resizing the canvas to fit it.
+fn draw(canvas: &HtmlCanvasElement, image: &RgbaImage) {
+    let image = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(image.as_raw()),
+        image.width(),
+        image.height(),
+    )
+    .map_err(|_| anyhow!("Failed to convert to ImageData."))
+    .unwrap();
+
+    canvas.set_width(image.width());
+    canvas.set_height(image.height());
+    let ctx = canvas
+        .get_context("2d")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<CanvasRenderingContext2d>()
+        .unwrap();
+    ctx.put_image_data(&image, 0.0, 0.0).unwrap();
+}
+
+/// What a drag across the canvas does, with both ends in canvas pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    /// A box to zoom into, drawn with the left button.
+    Select { from: (f64, f64), to: (f64, f64) },
+    /// Moving the view, with the middle or right button.
+    Pan { from: (f64, f64), to: (f64, f64) },
+}
+
+/// The view as a caption, with as many digits as the zoom makes visible.
+fn describe(view: View) -> String {
+    let digits = (view.zoom.log10().max(0.0) as usize) + 4;
+    let sign = if view.center.im < 0.0 { '-' } else { '+' };
+    format!(
+        "{:.digits$} {sign} {:.digits$}i at {:.3}×",
+        view.center.re,
+        view.center.im.abs(),
+        view.zoom,
+    )
+}
+
+#[component]
+pub fn mandelbrot_model(bounds: Bounds) -> impl IntoView {
+    // The view comes from the URL, so it can be bookmarked and shared.
+    let query = use_query_map();
+    let url_view = |query: &ParamsMap| {
+        View::from_query(
+            query.get_str("re"),
+            query.get_str("im"),
+            query.get_str("zoom"),
+        )
+    };
+    let view = RwSignal::new(query.with_untracked(url_view));
+    let history = RwSignal::new(ViewHistory::default());
+    let drag = RwSignal::new(None::<Drag>);
+    // When the wheel last zoomed, in milliseconds.
+    let last_wheel = StoredValue::new(f64::NEG_INFINITY);
+    let canvas: NodeRef<Canvas> = NodeRef::new();
+    let mode = RwSignal::new(RenderMode::default());
+    let coloring = RwSignal::new(Coloring::default());
+    let iterations = RwSignal::new(Iterations::default());
+    // What the server is up to, when it is rendering.
+    let status = RwSignal::new(None::<String>);
+    // Counts the images asked for, so a slow answer from the server doesn't
+    // replace a newer view.
+    let latest = StoredValue::new(0u64);
+
+    // Moves to `next`, remembering the view it leaves.
+    let go = move |next: View| {
+        let current = view.get_untracked();
+        if next != current {
+            history.update(|history| history.visit(current));
+            view.set(next);
+        }
+    };
+    let back = move |_| {
+        if let Some(Some(previous)) = history.try_update(|h| h.back(view.get_untracked())) {
+            view.set(previous);
+        }
+    };
+    let forward = move |_| {
+        if let Some(Some(next)) = history.try_update(|h| h.forward(view.get_untracked())) {
+            view.set(next);
+        }
+    };
+
+    Effect::new(move |_| {
+        let (view, mode, coloring) = (view.get(), mode.get(), coloring.get());
+        let limit = iterations
+            .get()
+            .limit(view.zoom, mode.max_iterations(bounds));
+        let Some(canvas) = canvas.get() else {
+            return;
+        };
+        latest.update_value(|latest| *latest += 1);
+        let ticket = latest.get_value();
+        set_timeout(
+            move || {
+                if latest.get_value() != ticket {
+                    return;
+                }
+                match mode {
+                    RenderMode::Local => {
+                        status.set(None);
+                        let (upper_left, lower_right) = view.corners(bounds);
+                        let image =
+                            generate_mandelbrot(bounds, upper_left, lower_right, coloring, limit);
+                        draw(&canvas, &image);
+                    }
+                    RenderMode::Server => {
+                        status.set(Some(String::from("Rendering on the server...")));
+                        spawn_local(async move {
+                            let request =
+                                RenderRequest::new(view, bounds, limit, coloring, Encoding::Png);
+                            let image = remote::fetch(request).await;
+                            if latest.get_value() != ticket {
+                                return;
+                            }
+                            match image {
+                                Ok(image) => {
+                                    status.set(None);
+                                    draw(&canvas, &image);
+                                }
+                                Err(e) => status.set(Some(format!("Server render failed: {e}"))),
+                            }
+                        });
+                    }
+                }
+            },
+            RENDER_DELAY,
+        );
+    });
+
+    // Keep the URL on the view without adding to the browser's history, which
+    // the Back and Forward buttons stand in for.
+    let navigate = use_navigate();
+    Effect::new(move |_| {
+        let view = view.get();
+        if query.with_untracked(url_view) != view {
+            navigate(
+                &view.to_query(),
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+    // Follow the URL when it is changed from outside the page.
+    Effect::new(move |_| {
+        let picked = query.with(url_view);
+        if picked != view.get_untracked() {
+            go(picked);
+        }
+    });
+
+    // Where the mouse is on the canvas, in canvas pixels, which the page may
+    // have scaled.
+    let canvas_point = move |event: &MouseEvent| {
+        let rect = canvas.get_untracked()?.get_bounding_client_rect();
+        if rect.width() == 0.0 || rect.height() == 0.0 {
+            return None;
+        }
+        Some((
+            (f64::from(event.client_x()) - rect.x()) * f64::from(bounds.width) / rect.width(),
+            (f64::from(event.client_y()) - rect.y()) * f64::from(bounds.height) / rect.height(),
+        ))
+    };
+
+    // Zooming must be able to stop the page scrolling, which passive
+    // listeners can't.
+    let active = UseEventListenerOptions::default().passive(false);
+    let _ = use_event_listener_with_options(
+        canvas,
+        ev::wheel,
+        move |event| {
+            event.prevent_default();
+            let Some((x, y)) = canvas_point(&event) else {
+                return;
+            };
+            let factor = if event.delta_y() < 0.0 {
+                ZOOM_STEP
+            } else {
+                1.0 / ZOOM_STEP
+            };
+            let mut next = view.get_untracked();
+            next.zoom_at(bounds, x, y, factor);
+            // A run of notches is one step back.
+            let now = js_sys::Date::now();
+            if now - last_wheel.get_value() < WHEEL_GESTURE_MS {
+                view.set(next);
+            } else {
+                go(next);
+            }
+            last_wheel.set_value(now);
+        },
+        active,
+    );
+
+    let on_mouse_down = move |event: MouseEvent| {
+        let Some(point) = canvas_point(&event) else {
+            return;
+        };
+        event.prevent_default();
+        drag.set(Some(match event.button() {
+            0 => Drag::Select {
+                from: point,
+                to: point,
+            },
+            _ => Drag::Pan {
+                from: point,
+                to: point,
+            },
+        }));
+    };
+
+    let on_mouse_move = move |event: MouseEvent| {
+        if drag.with_untracked(Option::is_none) {
+            return;
+        }
+        if let Some(point) = canvas_point(&event) {
+            drag.update(|drag| {
+                if let Some(Drag::Select { to, .. } | Drag::Pan { to, .. }) = drag {
+                    *to = point;
+                }
+            });
+        }
+    };
+
+    let on_mouse_up = move |event: MouseEvent| {
+        let Some(ended) = drag.get_untracked() else {
+            return;
+        };
+        drag.set(None);
+        let mut next = view.get_untracked();
+        match ended {
+            // A click centres on the point, zooming in, or out with Shift.
+            Drag::Select { from, to } if (to.0 - from.0).hypot(to.1 - from.1) < CLICK_DISTANCE => {
+                let factor = if event.shift_key() {
+                    1.0 / CLICK_ZOOM
+                } else {
+                    CLICK_ZOOM
+                };
+                next = View::new(next.point_at(bounds, to.0, to.1), next.zoom * factor);
+            }
+            Drag::Select { from, to } => next = next.fit(bounds, from, to),
+            Drag::Pan { from, to } => next.pan(bounds, to.0 - from.0, to.1 - from.1),
+        }
+        go(next);
+    };
+
+    // The box being drawn, and the image being dragged, as fractions of the
+    // canvas so they follow it however the page scales it.
+    let percent = move |(x, y): (f64, f64)| {
+        (
+            x * 100.0 / f64::from(bounds.width),
+            y * 100.0 / f64::from(bounds.height),
+        )
+    };
+    let selection_style = move || match drag.get() {
+        Some(Drag::Select { from, to }) => {
+            let (left, top) = percent((from.0.min(to.0), from.1.min(to.1)));
+            let (width, height) = percent(((to.0 - from.0).abs(), (to.1 - from.1).abs()));
+            format!("left: {left}%; top: {top}%; width: {width}%; height: {height}%")
+        }
+        _ => String::from("display: none"),
+    };
+    let pan_style = move || match drag.get() {
+        Some(Drag::Pan { from, to }) => {
+            let (x, y) = percent((to.0 - from.0, to.1 - from.1));
+            format!("transform: translate({x}%, {y}%)")
+        }
+        _ => String::new(),
+    };
+
+    view! {
+        <div class="mandelbrot-container">
+            <header class="header">
+                <h1> { "Mandelbrot" } </h1>
+            </header>
+            <div class="mandelbrot-viewer">
+                <div class="mandelbrot-canvas">
+                    <canvas
+                        node_ref=canvas
+                        width=bounds.width
+                        height=bounds.height
+                        style=pan_style
+                        on:mousedown=on_mouse_down
+                        on:mousemove=on_mouse_move
+                        on:mouseup=on_mouse_up
+                        on:mouseleave=move |_| drag.set(None)
+                        on:contextmenu=|event| event.prevent_default()
+                    />
+                    <div class="mandelbrot-selection" style=selection_style />
+                </div>
+            </div>
+            <div class="mandelbrot-controls">
+                <button
+                    class="mandelbrot-button"
+                    on:click=back
+                    disabled=move || !history.with(ViewHistory::can_go_back)
+                >
+                    "Back"
+                </button>
+                <button
+                    class="mandelbrot-button"
+                    on:click=forward
+                    disabled=move || !history.with(ViewHistory::can_go_forward)
+                >
+                    "Forward"
+                </button>
+                <button class="mandelbrot-button" on:click=move |_| go(View::default())>
+                    "Reset View"
+                </button>
+                <select
+                    class="mandelbrot-select"
+                    prop:value=move || mode.get().to_string()
+                    on:change=move |ev| {
+                        if let Ok(picked) = event_target_value(&ev).parse::<RenderMode>() {
+                            mode.set(picked);
+                        }
+                    }
+                >
+                    {RenderMode::iter().map(|option| {
+                        view! { <option value=option.to_string()>{format!("Render: {option}")}</option> }
+                    }).collect_view()}
+                </select>
+                <span class="mandelbrot-position">{move || describe(view.get())}</span>
+            </div>
+            <ColoringPicker coloring />
+            <IterationPicker
+                iterations
+                zoom=Signal::derive(move || view.get().zoom)
+                max=Signal::derive(move || mode.get().max_iterations(bounds))
+            />
+            <p class="mandelbrot-status">{move || status.get()}</p>
+            <p class="mandelbrot-help">
+                "Click to zoom in and Shift+Click to zoom out, drag a box to zoom into it, scroll to zoom around the cursor, and drag with the right or middle button to pan."
+            </p>
+            <p class="mandelbrot-caption">
+                "Visualizing the set of complex numbers c for which the function f_c(z) = z^2 + c does not diverge."
+            </p>
+            <footer class="mandelbrot-footer">
+                <p><a href="https://github.com/brongan/brongan.com" target="_blank">{ "source" }</a></p>
+            </footer>
+        </div>
+    }
+}
+
+#[component]
+pub fn mandelbrot() -> impl IntoView {
+    view! { <MandelbrotModel bounds={Bounds {width: 800, height: 500}} /> }
+}
@@ -0,0 +1,307 @@
+use image::{Rgba, RgbaImage};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+
+use super::render::{Bounds, Orbit};
+use crate::color::{mix, rgb};
+
+/// Gradients escaping points are coloured along. Each wraps around, so the
+/// last colour blends back into the first.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+pub enum Palette {
+    /// The deep blues and golds of Ultra Fractal's default.
+    #[default]
+    Ultra,
+    Fire,
+    Ocean,
+    Rainbow,
+    Grayscale,
+}
+
+impl Palette {
+    /// The colours the gradient runs through, as hex strings.
+    pub fn stops(self) -> &'static [&'static str] {
+        match self {
+            Palette::Ultra => &["#000764", "#206BCB", "#EDFFFF", "#FFAA00", "#000200"],
+            Palette::Fire => &["#000000", "#800000", "#FF5000", "#FFC800", "#FFFFC8"],
+            Palette::Ocean => &["#001428", "#005078", "#00A0C8", "#B4F0FF"],
+            Palette::Rainbow => &[
+                "#FF0000", "#FFFF00", "#00FF00", "#00FFFF", "#0000FF", "#FF00FF",
+            ],
+            Palette::Grayscale => &["#FFFFFF", "#000000"],
+        }
+    }
+}
+
+/// What decides where along the palette an escaping point is coloured.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+pub enum Shading {
+    /// How many steps it took to escape, in bands.
+    Banded,
+    /// The normalized iteration count, which blends the bands together.
+    #[default]
+    Smooth,
+    /// How many points in view escaped sooner, so the colours spread evenly
+    /// over the image however deep the zoom.
+    Histogram,
+}
+
+/// How the points that never escape are coloured.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+pub enum Interior {
+    #[default]
+    Black,
+    White,
+    /// Along the palette by how close the orbit came to the origin.
+    Orbit,
+}
+
+/// How the renderer turns orbits into colours.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coloring {
+    pub palette: Palette,
+    pub shading: Shading,
+    pub interior: Interior,
+    /// Steps of escape time one trip around the palette takes. Histograms
+    /// go around once over the whole image.
+    pub cycle: f64,
+    /// How far around the palette to start, from 0 to 1.
+    pub offset: f64,
+}
+
+impl Default for Coloring {
+    fn default() -> Coloring {
+        Coloring {
+            palette: Palette::default(),
+            shading: Shading::default(),
+            interior: Interior::default(),
+            cycle: 32.0,
+            offset: 0.0,
+        }
+    }
+}
+
+/// Works out the colour of each orbit of an image.
+pub struct Painter {
+    coloring: Coloring,
+    stops: Vec<[u8; 3]>,
+    /// For histogram shading, the share of escaping points whose normalized
+    /// count is below each whole number.
+    shares: Vec<f64>,
+}
+
+impl Painter {
+    /// A painter for `orbits`, which histogram shading needs all of.
+    pub fn new(coloring: Coloring, orbits: &[Orbit]) -> Painter {
+        let shares = if coloring.shading == Shading::Histogram {
+            histogram(orbits)
+        } else {
+            Vec::new()
+        };
+        Painter {
+            coloring,
+            stops: coloring
+                .palette
+                .stops()
+                .iter()
+                .map(|hex| rgb(hex))
+                .collect(),
+            shares,
+        }
+    }
+
+    /// The colour `t` of the way around the palette, past the offset.
+    pub fn gradient(&self, t: f64) -> [u8; 3] {
+        let t = (t + self.coloring.offset).rem_euclid(1.0) * self.stops.len() as f64;
+        let from = (t as usize).min(self.stops.len() - 1);
+        let to = (from + 1) % self.stops.len();
+        mix(self.stops[from], self.stops[to], t.fract())
+    }
+
+    pub fn color(&self, orbit: Orbit) -> Rgba<u8> {
+        let cycle = self.coloring.cycle.max(f64::EPSILON);
+        let [r, g, b] = match orbit {
+            Orbit::Escaped { count, smooth } => match self.coloring.shading {
+                Shading::Banded => self.gradient(f64::from(count) / cycle),
+                Shading::Smooth => self.gradient(smooth / cycle),
+                Shading::Histogram => self.gradient(self.share(smooth)),
+            },
+            Orbit::Bounded { closest } => match self.coloring.interior {
+                Interior::Black => [0, 0, 0],
+                Interior::White => [255, 255, 255],
+                Interior::Orbit => self.gradient(closest),
+            },
+        };
+        Rgba([r, g, b, 255])
+    }
+
+    /// The share of escaping points with a normalized count below `smooth`,
+    /// blending between whole numbers.
+    fn share(&self, smooth: f64) -> f64 {
+        let Some(last) = self.shares.len().checked_sub(1) else {
+            return 0.0;
+        };
+        let step = (smooth.max(0.0) as usize).min(last);
+        let next = (step + 1).min(last);
+        let (from, to) = (self.shares[step], self.shares[next]);
+        from + (to - from) * smooth.fract()
+    }
+
+    /// Colours `orbits`, given row by row, into an image of `bounds`.
+    pub fn paint(&self, bounds: Bounds, orbits: &[Orbit]) -> RgbaImage {
+        let mut image = RgbaImage::new(bounds.width, bounds.height);
+        for (pixel, &orbit) in image.pixels_mut().zip(orbits) {
+            *pixel = self.color(orbit);
+        }
+        image
+    }
+}
+
+/// For each whole number, the share of the escaping `orbits` whose
+/// normalized count is below it, ending with 1. Bucketing by the normalized
+/// count rather than the steps taken keeps it on the scale `Painter::share`
+/// looks it up on.
+fn histogram(orbits: &[Orbit]) -> Vec<f64> {
+    let mut counts = Vec::new();
+    for orbit in orbits {
+        if let Orbit::Escaped { smooth, .. } = orbit {
+            let count = smooth.max(0.0) as usize;
+            if counts.len() <= count {
+                counts.resize(count + 1, 0u64);
+            }
+            counts[count] += 1;
+        }
+    }
+    let total = counts.iter().sum::<u64>().max(1) as f64;
+    let mut sooner = 0;
+    let mut shares = Vec::with_capacity(counts.len() + 1);
+    for count in counts {
+        shares.push(sooner as f64 / total);
+        sooner += count;
+    }
+    shares.push(sooner as f64 / total);
+    shares
+}
+
+/// Picks the palette, how points are placed along it and how the inside of
+/// the set is coloured.
+#[component]
+pub fn ColoringPicker(#[prop(into)] coloring: RwSignal<Coloring>) -> impl IntoView {
+    view! {
+        <div class="mandelbrot-coloring">
+            <select
+                class="mandelbrot-select"
+                prop:value=move || coloring.get().palette.to_string()
+                on:change=move |ev| {
+                    if let Ok(palette) = event_target_value(&ev).parse::<Palette>() {
+                        coloring.update(|coloring| coloring.palette = palette);
+                    }
+                }
+            >
+                {Palette::iter()
+                    .map(|palette| view! { <option value=palette.to_string()>{palette.to_string()}</option> })
+                    .collect_view()}
+            </select>
+            <select
+                class="mandelbrot-select"
+                prop:value=move || coloring.get().shading.to_string()
+                on:change=move |ev| {
+                    if let Ok(shading) = event_target_value(&ev).parse::<Shading>() {
+                        coloring.update(|coloring| coloring.shading = shading);
+                    }
+                }
+            >
+                {Shading::iter()
+                    .map(|shading| view! { <option value=shading.to_string()>{shading.to_string()}</option> })
+                    .collect_view()}
+            </select>
+            <select
+                class="mandelbrot-select"
+                prop:value=move || coloring.get().interior.to_string()
+                on:change=move |ev| {
+                    if let Ok(interior) = event_target_value(&ev).parse::<Interior>() {
+                        coloring.update(|coloring| coloring.interior = interior);
+                    }
+                }
+            >
+                {Interior::iter()
+                    .map(|interior| {
+                        view! { <option value=interior.to_string()>{format!("Inside: {interior}")}</option> }
+                    })
+                    .collect_view()}
+            </select>
+            <label class="mandelbrot-slider">
+                {move || format!("Cycle: {} steps", coloring.get().cycle)}
+                <input
+                    type="range"
+                    min="1"
+                    max="256"
+                    step="1"
+                    prop:value=move || coloring.get().cycle
+                    prop:disabled=move || coloring.get().shading == Shading::Histogram
+                    on:change=move |ev| {
+                        if let Ok(cycle) = event_target_value(&ev).parse::<f64>() {
+                            coloring.update(|coloring| coloring.cycle = cycle.max(1.0));
+                        }
+                    }
+                />
+            </label>
+            <label class="mandelbrot-slider">
+                {move || format!("Offset: {:.0}%", coloring.get().offset * 100.0)}
+                <input
+                    type="range"
+                    min="0"
+                    max="1"
+                    step="0.01"
+                    prop:value=move || coloring.get().offset
+                    on:change=move |ev| {
+                        if let Ok(offset) = event_target_value(&ev).parse::<f64>() {
+                            coloring.update(|coloring| coloring.offset = offset.clamp(0.0, 1.0));
+                        }
+                    }
+                />
+            </label>
+        </div>
+    }
+}
+
+#[cfg(test)]
+#[path = "palette_test.rs"]
+mod palette_test;
@@ -0,0 +1,129 @@
+use super::*;
+use crate::mandelbrot::render::orbit;
+use num::Complex;
+
+fn escaped(count: u32) -> Orbit {
+    Orbit::Escaped {
+        count,
+        smooth: f64::from(count),
+    }
+}
+
+fn coloring(palette: Palette, shading: Shading) -> Coloring {
+    Coloring {
+        palette,
+        shading,
+        ..Coloring::default()
+    }
+}
+
+#[test]
+fn test_every_palette_parses() {
+    for palette in Palette::iter() {
+        for hex in palette.stops() {
+            rgb(hex);
+        }
+        assert_eq!(palette.to_string().parse::<Palette>(), Ok(palette));
+    }
+}
+
+#[test]
+fn test_gradient_wraps() {
+    let painter = Painter::new(coloring(Palette::Grayscale, Shading::Smooth), &[]);
+    assert_eq!(painter.gradient(0.0), [255, 255, 255]);
+    assert_eq!(painter.gradient(0.25), [128, 128, 128]);
+    assert_eq!(painter.gradient(0.5), [0, 0, 0]);
+    assert_eq!(painter.gradient(1.25), painter.gradient(0.25));
+    assert_eq!(painter.gradient(-0.75), painter.gradient(0.25));
+}
+
+#[test]
+fn test_offset_shifts_the_gradient() {
+    let shifted = Painter::new(
+        Coloring {
+            offset: 0.5,
+            ..coloring(Palette::Grayscale, Shading::Smooth)
+        },
+        &[],
+    );
+    assert_eq!(shifted.gradient(0.0), [0, 0, 0]);
+}
+
+#[test]
+fn test_smooth_blends_between_bands() {
+    let coloring = Coloring {
+        cycle: 4.0,
+        ..coloring(Palette::Grayscale, Shading::Smooth)
+    };
+    let painter = Painter::new(coloring, &[]);
+    let between = Orbit::Escaped {
+        count: 1,
+        smooth: 1.5,
+    };
+    assert_eq!(painter.color(escaped(1)), Rgba([128, 128, 128, 255]));
+    assert_eq!(painter.color(between), Rgba([64, 64, 64, 255]));
+
+    let banded = Painter::new(
+        Coloring {
+            shading: Shading::Banded,
+            ..coloring
+        },
+        &[],
+    );
+    assert_eq!(banded.color(between), banded.color(escaped(1)));
+}
+
+#[test]
+fn test_histogram_spreads_counts_evenly() {
+    // Half the points escape at once, the rest far later.
+    let orbits = [escaped(0), escaped(0), escaped(100), escaped(200)];
+    assert_eq!(histogram(&orbits)[..2], [0.0, 0.5]);
+    assert_eq!(histogram(&orbits)[200..], [0.75, 1.0]);
+    let painter = Painter::new(coloring(Palette::Grayscale, Shading::Histogram), &orbits);
+    assert_eq!(painter.share(0.0), 0.0);
+    assert_eq!(painter.share(100.0), 0.5);
+    assert_eq!(painter.share(200.0), 0.75);
+    assert_eq!(painter.color(escaped(100)), Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn test_histogram_places_real_orbits_in_order() {
+    // Points escaping ever slower, each in its own quarter of the palette.
+    let orbits: Vec<Orbit> = [2.0, 1.0, 0.4, 0.3]
+        .into_iter()
+        .map(|re| orbit(Complex::new(re, 0.0), 1000))
+        .collect();
+    let painter = Painter::new(coloring(Palette::Grayscale, Shading::Histogram), &orbits);
+    for (i, orbit) in orbits.iter().enumerate() {
+        let Orbit::Escaped { smooth, .. } = *orbit else {
+            panic!("{orbit:?} should escape");
+        };
+        let share = painter.share(smooth);
+        let quarter = i as f64 / 4.0;
+        assert!(
+            (quarter..quarter + 0.25).contains(&share),
+            "orbit {i} at {smooth} has share {share}"
+        );
+    }
+}
+
+#[test]
+fn test_interior() {
+    let inside = Orbit::Bounded { closest: 0.5 };
+    let mut coloring = Coloring::default();
+    assert_eq!(
+        Painter::new(coloring, &[]).color(inside),
+        Rgba([0, 0, 0, 255])
+    );
+    coloring.interior = Interior::White;
+    assert_eq!(
+        Painter::new(coloring, &[]).color(inside),
+        Rgba([255, 255, 255, 255])
+    );
+    coloring.interior = Interior::Orbit;
+    coloring.palette = Palette::Grayscale;
+    assert_eq!(
+        Painter::new(coloring, &[]).color(inside),
+        Rgba([0, 0, 0, 255])
+    );
+}
@@ -0,0 +1,362 @@
+use std::io::Cursor;
+
+use gloo_net::http::Request;
+use image::{ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString};
+
+use super::palette::{Coloring, Interior, Palette, Shading};
+use super::render::{
+    generate_mandelbrot, Bounds, DEFAULT_ITERATIONS, MAX_ITERATIONS, MIN_ITERATIONS,
+};
+use super::view::View;
+
+/// Where the server renders views.
+pub const ENDPOINT: &str = "/api/mandelbrot";
+/// The most pixels the server renders in one image, which is the page's
+/// canvas.
+const MAX_PIXELS: u64 = 800 * 500;
+/// The most orbit steps, pixels times the limit, the server takes for one
+/// image, which bounds how long anyone can keep its cores busy.
+pub const MAX_STEPS: u64 = 200_000_000;
+/// Goes in every image's URL, so bumping it when a change to rendering or
+/// colouring changes the images keeps browsers from showing old ones.
+pub const RENDER_VERSION: u32 = 1;
+/// The same request at this version always makes the same image, so it can
+/// be kept for good.
+#[cfg(feature = "ssr")]
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+/// Requests from another version are rendered as this one does, so aren't
+/// kept.
+#[cfg(feature = "ssr")]
+const STALE_CACHE_CONTROL: &str = "no-cache";
+/// Most images the server renders at once. Each uses every core, so more only
+/// slow each other down; the rest wait their turn.
+#[cfg(feature = "ssr")]
+const MAX_RENDERS: usize = 2;
+#[cfg(feature = "ssr")]
+static RENDERS: tokio::sync::Semaphore = tokio::sync::Semaphore::const_new(MAX_RENDERS);
+
+/// Where the page renders its views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, EnumString, strum::Display)]
+pub enum RenderMode {
+    /// In the browser.
+    #[default]
+    Local,
+    /// On the server, in parallel, fetching the image.
+    Server,
+}
+
+impl RenderMode {
+    /// The highest iteration limit an image of `bounds` can have. Only the
+    /// server holds images to its step budget.
+    pub fn max_iterations(self, bounds: Bounds) -> u32 {
+        match self {
+            RenderMode::Local => MAX_ITERATIONS,
+            RenderMode::Server => {
+                let pixels = (u64::from(bounds.width) * u64::from(bounds.height)).max(1);
+                (MAX_STEPS / pixels).clamp(u64::from(MIN_ITERATIONS), u64::from(MAX_ITERATIONS))
+                    as u32
+            }
+        }
+    }
+}
+
+/// How the server sends a rendered image back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Png,
+    Webp,
+    /// Bare RGBA bytes, a row at a time.
+    Rgba,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Png => "png",
+            Encoding::Webp => "webp",
+            Encoding::Rgba => "rgba",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Encoding::Png => "image/png",
+            Encoding::Webp => "image/webp",
+            Encoding::Rgba => "application/octet-stream",
+        }
+    }
+
+    pub fn encode(self, image: &RgbaImage) -> Result<Vec<u8>, RemoteError> {
+        let format = match self {
+            Encoding::Png => ImageFormat::Png,
+            Encoding::Webp => ImageFormat::WebP,
+            Encoding::Rgba => return Ok(image.as_raw().clone()),
+        };
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, format)?;
+        Ok(bytes.into_inner())
+    }
+
+    /// The image in `bytes`, which bare RGBA needs `bounds` to make sense of.
+    pub fn decode(self, bounds: Bounds, bytes: Vec<u8>) -> Result<RgbaImage, RemoteError> {
+        let format = match self {
+            Encoding::Png => ImageFormat::Png,
+            Encoding::Webp => ImageFormat::WebP,
+            Encoding::Rgba => {
+                let length = bytes.len();
+                return RgbaImage::from_raw(bounds.width, bounds.height, bytes)
+                    .ok_or(RemoteError::Length(length));
+            }
+        };
+        Ok(image::load_from_memory_with_format(&bytes, format)?.to_rgba8())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteError {
+    #[error("can't render an image with no pixels")]
+    Empty,
+    #[error("{0} is more than the {MAX_PIXELS} pixels the server renders")]
+    TooLarge(Bounds),
+    #[error("the view isn't at a finite point")]
+    NotFinite,
+    #[error("{0} iterations is more than the {MAX_ITERATIONS} the server follows orbits for")]
+    TooManyIterations(u32),
+    #[error("{0} steps is more than the {MAX_STEPS} the server takes for one image")]
+    TooMuchWork(u64),
+    #[error("request failed: {0}")]
+    Request(String),
+    #[error("server answered with status {0}")]
+    Status(u16),
+    #[error("{0} bytes aren't a whole image")]
+    Length(usize),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+}
+
+/// A view to render at a size and how to colour it, as the endpoint's query
+/// parameters. Colouring left out is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RenderRequest {
+    pub width: u32,
+    pub height: u32,
+    pub re: f64,
+    pub im: f64,
+    pub zoom: f64,
+    /// The most steps orbits are followed for.
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+    #[serde(default)]
+    pub palette: Palette,
+    #[serde(default)]
+    pub shading: Shading,
+    #[serde(default)]
+    pub interior: Interior,
+    #[serde(default = "default_cycle")]
+    pub cycle: f64,
+    #[serde(default)]
+    pub offset: f64,
+    #[serde(default)]
+    pub format: Encoding,
+    /// The `RENDER_VERSION` the page asking was built with.
+    #[serde(default, rename = "v")]
+    pub version: u32,
+}
+
+fn default_iterations() -> u32 {
+    DEFAULT_ITERATIONS
+}
+
+fn default_cycle() -> f64 {
+    Coloring::default().cycle
+}
+
+impl RenderRequest {
+    pub fn new(
+        view: View,
+        bounds: Bounds,
+        iterations: u32,
+        coloring: Coloring,
+        format: Encoding,
+    ) -> RenderRequest {
+        RenderRequest {
+            width: bounds.width,
+            height: bounds.height,
+            re: view.center.re,
+            im: view.center.im,
+            zoom: view.zoom,
+            iterations,
+            palette: coloring.palette,
+            shading: coloring.shading,
+            interior: coloring.interior,
+            cycle: coloring.cycle,
+            offset: coloring.offset,
+            format,
+            version: RENDER_VERSION,
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn view(&self) -> View {
+        View::new(num::Complex::new(self.re, self.im), self.zoom)
+    }
+
+    pub fn coloring(&self) -> Coloring {
+        Coloring {
+            palette: self.palette,
+            shading: self.shading,
+            interior: self.interior,
+            cycle: self.cycle,
+            offset: self.offset,
+        }
+    }
+
+    /// Where to fetch the image from.
+    pub fn url(&self) -> String {
+        format!(
+            "{ENDPOINT}?width={}&height={}&re={}&im={}&zoom={}&iterations={}\
+             &palette={}&shading={}&interior={}&cycle={}&offset={}&format={}&v={}",
+            self.width,
+            self.height,
+            self.re,
+            self.im,
+            self.zoom,
+            self.iterations,
+            self.palette,
+            self.shading,
+            self.interior,
+            self.cycle,
+            self.offset,
+            self.format.name(),
+            self.version
+        )
+    }
+
+    /// Whether the server should render this at all.
+    pub fn check(&self) -> Result<(), RemoteError> {
+        let pixels = u64::from(self.width) * u64::from(self.height);
+        let steps = pixels * u64::from(self.iterations);
+        if pixels == 0 {
+            Err(RemoteError::Empty)
+        } else if pixels > MAX_PIXELS {
+            Err(RemoteError::TooLarge(self.bounds()))
+        } else if ![self.re, self.im, self.zoom, self.cycle, self.offset]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            Err(RemoteError::NotFinite)
+        } else if self.iterations > MAX_ITERATIONS {
+            Err(RemoteError::TooManyIterations(self.iterations))
+        } else if steps > MAX_STEPS {
+            Err(RemoteError::TooMuchWork(steps))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Renders the view on this thread.
+    pub fn render(&self) -> RgbaImage {
+        let (upper_left, lower_right) = self.view().corners(self.bounds());
+        generate_mandelbrot(
+            self.bounds(),
+            upper_left,
+            lower_right,
+            self.coloring(),
+            self.iterations,
+        )
+    }
+
+    /// Renders the view with every core.
+    #[cfg(feature = "ssr")]
+    pub fn render_parallel(&self) -> RgbaImage {
+        use super::palette::Painter;
+        use super::render::{orbit_at, Point2d};
+        use rayon::prelude::*;
+
+        let bounds = self.bounds();
+        let (upper_left, lower_right) = self.view().corners(bounds);
+        let orbits: Vec<_> = (0..bounds.width * bounds.height)
+            .into_par_iter()
+            .map(|i| {
+                let point = Point2d {
+                    x: i % bounds.width,
+                    y: i / bounds.width,
+                };
+                orbit_at(bounds, point, upper_left, lower_right, self.iterations)
+            })
+            .collect();
+        Painter::new(self.coloring(), &orbits).paint(bounds, &orbits)
+    }
+}
+
+/// Renders the view the query asks for and sends it back as an image that
+/// caches forever, if the page asking is up to date.
+#[cfg(feature = "ssr")]
+pub async fn mandelbrot_get(
+    axum::extract::Query(request): axum::extract::Query<RenderRequest>,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+    use opentelemetry::global;
+    use opentelemetry::trace::Tracer;
+
+    if let Err(e) = request.check() {
+        return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+    }
+    let tracer = global::tracer("");
+    let _span = tracer.start("mandelbrot_get");
+    let Ok(_permit) = RENDERS.acquire().await else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    let rendered =
+        tokio::task::spawn_blocking(move || request.format.encode(&request.render_parallel()))
+            .await;
+    let cache_control = if request.version == RENDER_VERSION {
+        CACHE_CONTROL
+    } else {
+        STALE_CACHE_CONTROL
+    };
+    match rendered {
+        Ok(Ok(bytes)) => (
+            [
+                (header::CONTENT_TYPE, request.format.content_type()),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(Err(e)) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Asks the server to render `request`.
+pub async fn fetch(request: RenderRequest) -> Result<RgbaImage, RemoteError> {
+    let response = Request::get(&request.url())
+        .send()
+        .await
+        .map_err(|e| RemoteError::Request(e.to_string()))?;
+    if !response.ok() {
+        return Err(RemoteError::Status(response.status()));
+    }
+    let bytes = response
+        .binary()
+        .await
+        .map_err(|e| RemoteError::Request(e.to_string()))?;
+    request.format.decode(request.bounds(), bytes)
+}
+
+#[cfg(test)]
+#[path = "remote_test.rs"]
+mod remote_test;
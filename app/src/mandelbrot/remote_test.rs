@@ -0,0 +1,125 @@
+use super::*;
+use crate::mandelbrot::render::Iterations;
+
+const BOUNDS: Bounds = Bounds {
+    width: 40,
+    height: 30,
+};
+
+fn request(format: Encoding) -> RenderRequest {
+    RenderRequest::new(View::default(), BOUNDS, 100, Coloring::default(), format)
+}
+
+#[test]
+fn test_url() {
+    let request = RenderRequest {
+        width: 800,
+        height: 500,
+        re: -0.5,
+        im: 0.25,
+        zoom: 2.0,
+        iterations: 300,
+        palette: Palette::Fire,
+        shading: Shading::Histogram,
+        interior: Interior::Orbit,
+        cycle: 16.0,
+        offset: 0.5,
+        format: Encoding::Webp,
+        version: 3,
+    };
+    assert_eq!(
+        request.url(),
+        "/api/mandelbrot?width=800&height=500&re=-0.5&im=0.25&zoom=2&iterations=300\
+         &palette=Fire&shading=Histogram&interior=Orbit&cycle=16&offset=0.5&format=webp&v=3"
+    );
+    assert_eq!(request.coloring().palette, Palette::Fire);
+}
+
+#[test]
+fn test_encodings_round_trip() {
+    for format in [Encoding::Png, Encoding::Webp, Encoding::Rgba] {
+        let image = request(format).render();
+        let bytes = format.encode(&image).unwrap();
+        assert_eq!(format.decode(BOUNDS, bytes).unwrap(), image, "{format:?}");
+    }
+}
+
+#[test]
+fn test_short_rgba() {
+    assert!(matches!(
+        Encoding::Rgba.decode(BOUNDS, vec![0; 12]),
+        Err(RemoteError::Length(12))
+    ));
+}
+
+#[test]
+fn test_check() {
+    assert!(request(Encoding::Png).check().is_ok());
+    let empty = RenderRequest {
+        width: 0,
+        ..request(Encoding::Png)
+    };
+    assert!(matches!(empty.check(), Err(RemoteError::Empty)));
+    let huge = RenderRequest {
+        width: 10_000,
+        height: 10_000,
+        ..request(Encoding::Png)
+    };
+    assert!(matches!(huge.check(), Err(RemoteError::TooLarge(_))));
+    let lost = RenderRequest {
+        re: f64::NAN,
+        ..request(Encoding::Png)
+    };
+    assert!(matches!(lost.check(), Err(RemoteError::NotFinite)));
+    let endless = RenderRequest {
+        iterations: u32::MAX,
+        ..request(Encoding::Png)
+    };
+    assert!(matches!(
+        endless.check(),
+        Err(RemoteError::TooManyIterations(_))
+    ));
+    let slow = RenderRequest {
+        width: 800,
+        height: 500,
+        iterations: 5000,
+        ..request(Encoding::Png)
+    };
+    assert!(matches!(
+        slow.check(),
+        Err(RemoteError::TooMuchWork(2_000_000_000))
+    ));
+}
+
+#[test]
+fn test_request_version() {
+    assert_eq!(request(Encoding::Png).version, RENDER_VERSION);
+    assert!(request(Encoding::Png)
+        .url()
+        .ends_with(&format!("&v={RENDER_VERSION}")));
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn test_parallel_render_matches() {
+    let request = request(Encoding::Png);
+    assert_eq!(request.render_parallel(), request.render());
+}
+
+#[test]
+fn test_iteration_budget() {
+    let page = Bounds {
+        width: 800,
+        height: 500,
+    };
+    assert_eq!(RenderMode::Local.max_iterations(page), MAX_ITERATIONS);
+    assert_eq!(RenderMode::Server.max_iterations(page), 500);
+    let max = RenderMode::Server.max_iterations(page);
+    assert_eq!(Iterations::Fixed(100_000).limit(1.0, max), 500);
+    assert_eq!(Iterations::Auto.limit(View::MAX_ZOOM, max), 500);
+    let huge = Bounds {
+        width: 100_000,
+        height: 100_000,
+    };
+    assert_eq!(RenderMode::Server.max_iterations(huge), MIN_ITERATIONS);
+}
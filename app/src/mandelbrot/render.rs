@@ -0,0 +1,172 @@
+use crate::point2d::Point2D;
+use anyhow::anyhow;
+use image::RgbaImage;
+use num::Complex;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use super::palette::{Coloring, Painter};
+
+pub type Point2d = Point2D<u32>;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.width, self.height)
+    }
+}
+
+fn parse_pair<T: FromStr>(s: &str, separator: char) -> Option<(T, T)> {
+    match s.find(separator) {
+        None => None,
+        Some(index) => match (T::from_str(&s[..index]), T::from_str(&s[index + 1..])) {
+            (Ok(l), Ok(r)) => Some((l, r)),
+            _ => None,
+        },
+    }
+}
+
+impl FromStr for Bounds {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        parse_pair(s, 'x')
+            .map(|(width, height)| Bounds { width, height })
+            .ok_or_else(|| anyhow!("Failed to parse bounds."))
+    }
+}
+
+/// Steps an orbit is followed for, unless told otherwise, before the point is
+/// taken to be in the set.
+pub const DEFAULT_ITERATIONS: u32 = 255;
+/// The fewest and most steps the limit can be set to.
+pub const MIN_ITERATIONS: u32 = 16;
+pub const MAX_ITERATIONS: u32 = 100_000;
+/// Steps the automatic limit starts from, and adds for each tenfold zoom.
+const AUTO_BASE: f64 = 100.0;
+const AUTO_PER_DECADE: f64 = 150.0;
+/// How far from the origin, squared, an orbit must get to have escaped. Far
+/// past the 2 that proves it, so the normalized iteration count is smooth.
+const BAILOUT: f64 = 256.0 * 256.0;
+
+/// Where the orbit of a point under z -> z^2 + c went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orbit {
+    /// It escaped after `count` steps, or `smooth` steps counting how far
+    /// past the bailout that last step went.
+    Escaped { count: u32, smooth: f64 },
+    /// It stayed in for every step, coming `closest` to the origin.
+    Bounded { closest: f64 },
+}
+
+/// Follows the orbit of `c` for up to `limit` steps.
+pub fn orbit(c: Complex<f64>, limit: u32) -> Orbit {
+    let mut z = Complex { re: 0.0, im: 0.0 };
+    let mut closest = f64::INFINITY;
+    for i in 0..limit {
+        let norm = z.norm_sqr();
+        if norm > BAILOUT {
+            // log2(ln |z|), and |z| is the square root of `norm`.
+            let smooth = f64::from(i) + 1.0 - (norm.ln() / 2.0).log2();
+            return Orbit::Escaped {
+                count: i,
+                smooth: smooth.max(0.0),
+            };
+        }
+        if i > 0 {
+            closest = closest.min(norm);
+        }
+        z = z * z + c;
+    }
+    Orbit::Bounded {
+        closest: closest.sqrt(),
+    }
+}
+
+pub fn pixel_to_point(
+    bounds: Bounds,
+    pixel: Point2d,
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+) -> Complex<f64> {
+    let (width, height) = (
+        lower_right.re - upper_left.re,
+        upper_left.im - lower_right.im,
+    );
+    Complex {
+        re: upper_left.re + pixel.x as f64 * width / bounds.width as f64,
+        im: upper_left.im - pixel.y as f64 * height / bounds.height as f64,
+    }
+}
+
+/// How many steps orbits are followed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Iterations {
+    /// Always this many.
+    Fixed(u32),
+    /// More the deeper the zoom, since the boundary gets ever slower to
+    /// escape from.
+    #[default]
+    Auto,
+}
+
+impl Iterations {
+    /// The limit at `zoom`, no more than `max`.
+    pub fn limit(self, zoom: f64, max: u32) -> u32 {
+        let limit = match self {
+            Iterations::Fixed(limit) => limit,
+            Iterations::Auto => {
+                (AUTO_BASE + AUTO_PER_DECADE * zoom.log10().max(0.0)).round() as u32
+            }
+        };
+        limit.clamp(MIN_ITERATIONS, max.max(MIN_ITERATIONS))
+    }
+}
+
+/// The orbit, followed for up to `limit` steps, of the point under `pixel` of
+/// an image of `bounds` spanning the plane from `upper_left` to `lower_right`.
+pub fn orbit_at(
+    bounds: Bounds,
+    pixel: Point2d,
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+    limit: u32,
+) -> Orbit {
+    orbit(
+        pixel_to_point(bounds, pixel, upper_left, lower_right),
+        limit,
+    )
+}
+
+/// The orbit of every pixel of an image of `bounds`, row by row.
+fn orbits(
+    bounds: Bounds,
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+    limit: u32,
+) -> Vec<Orbit> {
+    (0..bounds.height)
+        .flat_map(|y| (0..bounds.width).map(move |x| Point2d { x, y }))
+        .map(|pixel| orbit_at(bounds, pixel, upper_left, lower_right, limit))
+        .collect()
+}
+
+pub fn generate_mandelbrot(
+    bounds: Bounds,
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+    coloring: Coloring,
+    limit: u32,
+) -> RgbaImage {
+    let orbits = orbits(bounds, upper_left, lower_right, limit);
+    Painter::new(coloring, &orbits).paint(bounds, &orbits)
+}
+
+#[cfg(test)]
+#[path = "render_test.rs"]
+mod render_test;
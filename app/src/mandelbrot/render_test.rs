@@ -0,0 +1,81 @@
+use super::*;
+
+#[test]
+fn test_inside_points_stay_bounded() {
+    assert_eq!(
+        orbit(Complex::new(0.0, 0.0), DEFAULT_ITERATIONS),
+        Orbit::Bounded { closest: 0.0 }
+    );
+    assert!(matches!(
+        orbit(Complex::new(-1.0, 0.0), DEFAULT_ITERATIONS),
+        Orbit::Bounded { closest } if closest == 0.0
+    ));
+}
+
+#[test]
+fn test_outside_points_escape() {
+    let Orbit::Escaped { count, smooth } = orbit(Complex::new(2.0, 0.0), DEFAULT_ITERATIONS) else {
+        panic!("2 is outside the set");
+    };
+    // 2, 6, 38, 1446 takes four steps past the bailout.
+    assert_eq!(count, 4);
+    assert!(smooth > 0.0 && smooth < f64::from(count) + 1.0);
+}
+
+#[test]
+fn test_smooth_count_is_continuous() {
+    // Neighbouring points either side of a band edge land close together.
+    let smooth = |re: f64| match orbit(Complex::new(re, 0.0), DEFAULT_ITERATIONS) {
+        Orbit::Escaped { smooth, .. } => smooth,
+        Orbit::Bounded { .. } => panic!("{re} is outside the set"),
+    };
+    let steps: Vec<f64> = (0..200)
+        .map(|i| smooth(0.3 + f64::from(i) * 1e-4))
+        .collect();
+    for pair in steps.windows(2) {
+        assert!((pair[0] - pair[1]).abs() < 0.1, "{pair:?}");
+    }
+}
+
+#[test]
+fn test_parse_bounds() {
+    assert_eq!(
+        "800x500".parse::<Bounds>().unwrap(),
+        Bounds {
+            width: 800,
+            height: 500
+        }
+    );
+    assert!("800".parse::<Bounds>().is_err());
+}
+
+#[test]
+fn test_counts_past_255() {
+    // Just past the cusp of the main cardioid, orbits crawl out slowly.
+    let c = Complex::new(0.2501, 0.0);
+    assert!(matches!(orbit(c, 255), Orbit::Bounded { .. }));
+    let Orbit::Escaped { count, smooth } = orbit(c, 1000) else {
+        panic!("{c} is outside the set");
+    };
+    assert!(count > 255, "{count}");
+    assert!(smooth > 255.0);
+}
+
+#[test]
+fn test_iteration_limits() {
+    assert_eq!(Iterations::Fixed(500).limit(1e9, MAX_ITERATIONS), 500);
+    assert_eq!(
+        Iterations::Fixed(1).limit(1.0, MAX_ITERATIONS),
+        MIN_ITERATIONS
+    );
+    assert_eq!(
+        Iterations::Fixed(u32::MAX).limit(1.0, MAX_ITERATIONS),
+        MAX_ITERATIONS
+    );
+    assert_eq!(Iterations::Auto.limit(0.5, MAX_ITERATIONS), 100);
+    assert_eq!(Iterations::Auto.limit(1.0, MAX_ITERATIONS), 100);
+    assert_eq!(Iterations::Auto.limit(1000.0, MAX_ITERATIONS), 550);
+    assert!(
+        Iterations::Auto.limit(1e13, MAX_ITERATIONS) > Iterations::Auto.limit(1e6, MAX_ITERATIONS)
+    );
+}
@@ -0,0 +1,169 @@
+use num::Complex;
+
+use super::Bounds;
+
+/// Which part of the complex plane the canvas shows. Pixels are square, so
+/// the zoom fixes the height of the view and the canvas's shape its width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    /// The point in the middle of the canvas.
+    pub center: Complex<f64>,
+    /// How many times closer than the whole set the view is.
+    pub zoom: f64,
+}
+
+impl Default for View {
+    /// The valley between the main cardioid and the period two bulb.
+    fn default() -> View {
+        View {
+            center: Complex::new(-1.1, 0.275),
+            zoom: 20.0,
+        }
+    }
+}
+
+impl View {
+    /// The height of the plane shown at a zoom of one, which takes in the
+    /// whole set.
+    pub const BASE_HEIGHT: f64 = 3.0;
+    pub const MIN_ZOOM: f64 = 0.5;
+    /// Past this, neighbouring pixels are too close together for an `f64` to
+    /// tell apart.
+    pub const MAX_ZOOM: f64 = 1e13;
+
+    /// A view of `center`, with the zoom clamped to what can be shown.
+    pub fn new(center: Complex<f64>, zoom: f64) -> View {
+        View {
+            center,
+            zoom: zoom.clamp(View::MIN_ZOOM, View::MAX_ZOOM),
+        }
+    }
+
+    /// The distance on the plane between neighbouring pixels of a canvas
+    /// `height` pixels high.
+    pub fn pixel_size(&self, height: u32) -> f64 {
+        View::BASE_HEIGHT / self.zoom / f64::from(height.max(1))
+    }
+
+    /// The upper left and lower right corners of the view on a canvas of
+    /// `bounds`.
+    pub fn corners(&self, bounds: Bounds) -> (Complex<f64>, Complex<f64>) {
+        let pixel = self.pixel_size(bounds.height);
+        let half = Complex::new(
+            f64::from(bounds.width) * pixel / 2.0,
+            f64::from(bounds.height) * pixel / 2.0,
+        );
+        (
+            Complex::new(self.center.re - half.re, self.center.im + half.im),
+            Complex::new(self.center.re + half.re, self.center.im - half.im),
+        )
+    }
+
+    /// The point under (`x`, `y`) on a canvas of `bounds`, in canvas pixels.
+    pub fn point_at(&self, bounds: Bounds, x: f64, y: f64) -> Complex<f64> {
+        let pixel = self.pixel_size(bounds.height);
+        Complex::new(
+            self.center.re + (x - f64::from(bounds.width) / 2.0) * pixel,
+            self.center.im - (y - f64::from(bounds.height) / 2.0) * pixel,
+        )
+    }
+
+    /// Zooms in by `factor`, keeping the point under (`x`, `y`) still.
+    pub fn zoom_at(&mut self, bounds: Bounds, x: f64, y: f64, factor: f64) {
+        let point = self.point_at(bounds, x, y);
+        self.zoom = (self.zoom * factor).clamp(View::MIN_ZOOM, View::MAX_ZOOM);
+        let moved = self.point_at(bounds, x, y);
+        self.center += point - moved;
+    }
+
+    /// Drags the view by a distance in canvas pixels.
+    pub fn pan(&mut self, bounds: Bounds, dx: f64, dy: f64) {
+        let pixel = self.pixel_size(bounds.height);
+        self.center.re -= dx * pixel;
+        self.center.im += dy * pixel;
+    }
+
+    /// A view filling the canvas with the box between two corners, in canvas
+    /// pixels, as much as its shape allows.
+    pub fn fit(&self, bounds: Bounds, from: (f64, f64), to: (f64, f64)) -> View {
+        let (width, height) = ((to.0 - from.0).abs(), (to.1 - from.1).abs());
+        let factor = (f64::from(bounds.width) / width).min(f64::from(bounds.height) / height);
+        let center = self.point_at(bounds, (from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+        View::new(center, self.zoom * factor)
+    }
+
+    /// The view from the `re`, `im` and `zoom` query parameters, with the
+    /// default's for any that are missing or aren't numbers.
+    pub fn from_query(re: Option<&str>, im: Option<&str>, zoom: Option<&str>) -> View {
+        let default = View::default();
+        let parse = |value: Option<&str>, default: f64| {
+            value
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|value| value.is_finite())
+                .unwrap_or(default)
+        };
+        View::new(
+            Complex::new(parse(re, default.center.re), parse(im, default.center.im)),
+            parse(zoom, default.zoom),
+        )
+    }
+
+    /// The query string that shows this view again.
+    pub fn to_query(self) -> String {
+        format!(
+            "?re={}&im={}&zoom={}",
+            self.center.re, self.center.im, self.zoom
+        )
+    }
+}
+
+/// The views left behind, for going back and forward through them like a
+/// browser's history.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ViewHistory {
+    back: Vec<View>,
+    forward: Vec<View>,
+}
+
+impl ViewHistory {
+    /// Oldest views are forgotten past this many.
+    const LIMIT: usize = 100;
+
+    /// Remembers `left`, the view being moved away from, forgetting any that
+    /// had been gone back from.
+    pub fn visit(&mut self, left: View) {
+        if self.back.last() != Some(&left) {
+            self.back.push(left);
+        }
+        if self.back.len() > ViewHistory::LIMIT {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// The view before `current`, if there is one.
+    pub fn back(&mut self, current: View) -> Option<View> {
+        let view = self.back.pop()?;
+        self.forward.push(current);
+        Some(view)
+    }
+
+    /// The view gone back from to reach `current`, if there is one.
+    pub fn forward(&mut self, current: View) -> Option<View> {
+        let view = self.forward.pop()?;
+        self.back.push(current);
+        Some(view)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[cfg(test)]
+#[path = "view_test.rs"]
+mod view_test;
@@ -0,0 +1,101 @@
+use super::*;
+
+const BOUNDS: Bounds = Bounds {
+    width: 400,
+    height: 300,
+};
+
+fn assert_close(a: Complex<f64>, b: Complex<f64>) {
+    assert!((a - b).norm() < 1e-12, "{a} != {b}");
+}
+
+#[test]
+fn test_corners() {
+    let view = View::new(Complex::new(-0.5, 0.0), 1.0);
+    let (upper_left, lower_right) = view.corners(BOUNDS);
+    assert_close(upper_left, Complex::new(-2.5, 1.5));
+    assert_close(lower_right, Complex::new(1.5, -1.5));
+    assert_close(view.point_at(BOUNDS, 0.0, 0.0), upper_left);
+    assert_close(view.point_at(BOUNDS, 200.0, 150.0), view.center);
+}
+
+#[test]
+fn test_zoom_keeps_the_cursor_still() {
+    let mut view = View::default();
+    let before = view.point_at(BOUNDS, 100.0, 60.0);
+    view.zoom_at(BOUNDS, 100.0, 60.0, 2.0);
+    assert_eq!(view.zoom, 40.0);
+    assert_close(view.point_at(BOUNDS, 100.0, 60.0), before);
+    view.zoom_at(BOUNDS, 100.0, 60.0, 0.25);
+    assert_eq!(view.zoom, 10.0);
+    assert_close(view.point_at(BOUNDS, 100.0, 60.0), before);
+}
+
+#[test]
+fn test_zoom_is_clamped() {
+    let mut view = View::default();
+    view.zoom_at(BOUNDS, 0.0, 0.0, 1e20);
+    assert_eq!(view.zoom, View::MAX_ZOOM);
+    view.zoom_at(BOUNDS, 0.0, 0.0, 1e-20);
+    assert_eq!(view.zoom, View::MIN_ZOOM);
+}
+
+#[test]
+fn test_pan() {
+    let mut view = View::default();
+    let before = view.point_at(BOUNDS, 100.0, 100.0);
+    view.pan(BOUNDS, 30.0, -20.0);
+    assert_close(view.point_at(BOUNDS, 130.0, 80.0), before);
+}
+
+#[test]
+fn test_fit() {
+    let view = View::new(Complex::new(0.0, 0.0), 1.0);
+    // A box half as tall as the canvas, and less than half as wide.
+    let fitted = view.fit(BOUNDS, (250.0, 100.0), (150.0, 250.0));
+    assert_eq!(fitted.zoom, 2.0);
+    assert_close(fitted.center, view.point_at(BOUNDS, 200.0, 175.0));
+}
+
+#[test]
+fn test_query_round_trip() {
+    let view = View::new(Complex::new(-0.743643887037151, 0.13182590420533), 1234.5);
+    let query = view.to_query();
+    let params: Vec<_> = query[1..]
+        .split('&')
+        .map(|param| param.split_once('=').unwrap().1)
+        .collect();
+    assert_eq!(
+        View::from_query(Some(params[0]), Some(params[1]), Some(params[2])),
+        view
+    );
+}
+
+#[test]
+fn test_query_defaults() {
+    assert_eq!(View::from_query(None, None, None), View::default());
+    let view = View::from_query(Some("0.25"), Some("NaN"), Some("1e40"));
+    assert_eq!(view.center, Complex::new(0.25, View::default().center.im));
+    assert_eq!(view.zoom, View::MAX_ZOOM);
+}
+
+#[test]
+fn test_history() {
+    let views: Vec<View> = (1..=3)
+        .map(|zoom| View::new(Complex::new(0.0, 0.0), f64::from(zoom)))
+        .collect();
+    let mut history = ViewHistory::default();
+    assert!(!history.can_go_back());
+    history.visit(views[0]);
+    history.visit(views[1]);
+    assert_eq!(history.back(views[2]), Some(views[1]));
+    assert_eq!(history.back(views[1]), Some(views[0]));
+    assert_eq!(history.back(views[0]), None);
+    assert!(history.can_go_forward());
+    assert_eq!(history.forward(views[0]), Some(views[1]));
+
+    // Going somewhere new drops the views ahead.
+    history.visit(views[1]);
+    assert!(!history.can_go_forward());
+    assert_eq!(history.back(views[2]), Some(views[1]));
+}
@@ -1,6 +1,11 @@
-use app::chip8::emulator::cpu::Keypad;
-use app::chip8::emulator::emulator::Emulator;
+use app::chip8::emulator::cpu::{Instruction, Keypad, Register, CPU};
+use app::chip8::emulator::engine::Emulator;
+use app::chip8::emulator::observer::Chip8Observer;
 use app::chip8::emulator::quirks::Quirks;
+use app::chip8::emulator::timing::Timing;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
 
 #[test]
 fn test_emulator_new() {
@@ -59,3 +64,266 @@ fn test_emulator_reset() {
     emulator.reset();
     assert_eq!(emulator.instruction_counter(), 0);
 }
+
+#[test]
+fn test_emulator_run_frame_ticks_timers_once() {
+    // ROM:
+    // 0x603C - LD V0, 60
+    // 0xF015 - LD DT, V0
+    // 0x1204 - JP 0x204 (infinite loop)
+    let rom = vec![0x60, 0x3C, 0xF0, 0x15, 0x12, 0x04];
+    let mut emulator = Emulator::new(Some(rom));
+
+    emulator.run_frame(Keypad::default());
+    assert_eq!(emulator.frame_counter(), 1);
+    assert_eq!(emulator.cpu().get_delay_timer(), 59);
+
+    for _ in 1..Emulator::FRAME_RATE {
+        emulator.run_frame(Keypad::default());
+    }
+    assert_eq!(emulator.instruction_counter(), Emulator::TARGET_IPS as u64);
+    assert_eq!(emulator.cpu().get_delay_timer(), 0);
+}
+
+#[test]
+fn test_emulator_update_runs_whole_frames() {
+    let mut emulator = Emulator::new(Some(vec![0x12, 0x00]));
+
+    // Less than a frame does nothing.
+    emulator.update(Keypad::default(), Duration::from_millis(10));
+    assert_eq!(emulator.frame_counter(), 0);
+    assert_eq!(emulator.instruction_counter(), 0);
+
+    emulator.update(Keypad::default(), Duration::from_millis(990));
+    assert_eq!(emulator.frame_counter(), 60);
+    assert_eq!(emulator.instruction_counter(), 700);
+}
+
+#[test]
+fn test_emulator_step_finishes_partial_frame() {
+    let mut emulator = Emulator::new(Some(vec![0x12, 0x00]));
+
+    emulator.step(Keypad::default(), 5);
+    assert_eq!(emulator.frame_counter(), 0);
+
+    // The first frame's budget is 11 instructions, 5 of which already ran.
+    emulator.run_frame(Keypad::default());
+    assert_eq!(emulator.frame_counter(), 1);
+    assert_eq!(emulator.instruction_counter(), 11);
+}
+
+#[test]
+fn test_emulator_display_wait_draws_once_per_frame() {
+    // ROM:
+    // 0x603C - LD V0, 60
+    // 0xF015 - LD DT, V0
+    // 0xD001 - DRW V0, V0, 1
+    // 0x1204 - JP 0x204 (draw forever)
+    let rom = vec![0x60, 0x3C, 0xF0, 0x15, 0xD0, 0x01, 0x12, 0x04];
+    let mut emulator = Emulator::new(Some(rom));
+    emulator.quirks = Quirks {
+        display_wait: true,
+        ..Quirks::MODERN
+    };
+
+    // Frame 1 runs up to and including the first draw.
+    emulator.run_frame(Keypad::default());
+    assert_eq!(emulator.instruction_counter(), 3);
+
+    // Every following frame is a jump and a draw.
+    for _ in 1..Emulator::FRAME_RATE {
+        emulator.run_frame(Keypad::default());
+    }
+    assert_eq!(emulator.instruction_counter(), 3 + 2 * 59);
+    // Timers still tick once per frame despite the CPU idling.
+    assert_eq!(emulator.cpu().get_delay_timer(), 0);
+}
+
+#[test]
+fn test_emulator_display_wait_latches_screen_on_vblank() {
+    // ROM:
+    // 0xA050 - LD I, 0x050 (font "0")
+    // 0xD001 - DRW V0, V0, 1
+    // 0x1204 - JP 0x204 (infinite loop)
+    let rom = vec![0xA0, 0x50, 0xD0, 0x01, 0x12, 0x04];
+    let mut emulator = Emulator::new(Some(rom));
+    emulator.quirks = Quirks {
+        display_wait: true,
+        ..Quirks::MODERN
+    };
+
+    emulator.step(Keypad::default(), 1);
+    assert!(!emulator.screen().0[0][0]);
+
+    emulator.step(Keypad::default(), 1);
+    assert!(emulator.cpu().get_screen().0[0][0]);
+    assert!(emulator.screen().0[0][0]);
+    assert_eq!(emulator.frame_counter(), 1);
+}
+
+#[test]
+fn test_emulator_vip_timing_counts_cycles() {
+    // ROM:
+    // 0x1200 - JP 0x200 (63 cycles per loop)
+    let mut emulator = Emulator::new(Some(vec![0x12, 0x00]));
+    emulator.timing = Timing::CosmacVip;
+
+    emulator.run_frame(Keypad::default());
+    // 2615 cycles fit 41 jumps with one interrupted jump carried over.
+    assert_eq!(emulator.instruction_counter(), 42);
+    assert_eq!(emulator.frame_counter(), 1);
+}
+
+#[test]
+fn test_emulator_vip_timing_draws_are_expensive() {
+    // ROM:
+    // 0xD00F - DRW V0, V0, 15
+    // 0x1200 - JP 0x200
+    let draw_loop = vec![0xD0, 0x0F, 0x12, 0x00];
+    let mut fixed = Emulator::new(Some(draw_loop.clone()));
+    let mut vip = Emulator::new(Some(draw_loop));
+    vip.timing = Timing::CosmacVip;
+
+    for _ in 0..Emulator::FRAME_RATE {
+        fixed.run_frame(Keypad::default());
+        vip.run_frame(Keypad::default());
+    }
+    assert_eq!(fixed.instruction_counter(), 700);
+    assert!(vip.instruction_counter() < 700);
+    assert_eq!(vip.frame_counter(), 60);
+}
+
+#[derive(Debug, PartialEq)]
+enum Observed {
+    Before(u16),
+    After(u16),
+    MemoryWrite(u16, u8),
+    Draw(u8, u8, u8, bool),
+    SoundStart,
+    SoundStop,
+    KeyWait(u8),
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Rc<RefCell<Vec<Observed>>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<Observed> {
+        self.0.take()
+    }
+}
+
+impl Chip8Observer for Recorder {
+    fn before_instruction(&mut self, cpu: &CPU, _instruction: &Instruction) {
+        self.0.borrow_mut().push(Observed::Before(cpu.get_pc()));
+    }
+
+    fn after_instruction(&mut self, cpu: &CPU, _instruction: &Instruction) {
+        self.0.borrow_mut().push(Observed::After(cpu.get_pc()));
+    }
+
+    fn memory_write(&mut self, addr: u16, value: u8) {
+        self.0.borrow_mut().push(Observed::MemoryWrite(addr, value));
+    }
+
+    fn draw(&mut self, x: u8, y: u8, height: u8, collision: bool) {
+        self.0
+            .borrow_mut()
+            .push(Observed::Draw(x, y, height, collision));
+    }
+
+    fn sound_start(&mut self) {
+        self.0.borrow_mut().push(Observed::SoundStart);
+    }
+
+    fn sound_stop(&mut self) {
+        self.0.borrow_mut().push(Observed::SoundStop);
+    }
+
+    fn key_wait(&mut self, register: Register) {
+        self.0.borrow_mut().push(Observed::KeyWait(register as u8));
+    }
+}
+
+#[test]
+fn test_observer_sees_instructions_and_memory_writes() {
+    // ROM:
+    // 0x60FE - LD V0, 254
+    // 0xA300 - LD I, 0x300
+    // 0xF033 - LD B, V0
+    let mut emulator = Emulator::new(Some(vec![0x60, 0xFE, 0xA3, 0x00, 0xF0, 0x33]));
+    let recorder = Recorder::default();
+    emulator.add_observer(recorder.clone());
+
+    emulator.step(Keypad::default(), 3);
+    assert_eq!(
+        recorder.take(),
+        vec![
+            Observed::Before(0x200),
+            Observed::After(0x202),
+            Observed::Before(0x202),
+            Observed::After(0x204),
+            Observed::Before(0x204),
+            Observed::MemoryWrite(0x300, 2),
+            Observed::MemoryWrite(0x301, 5),
+            Observed::MemoryWrite(0x302, 4),
+            Observed::After(0x206),
+        ]
+    );
+}
+
+#[test]
+fn test_observer_sees_draws_with_collisions() {
+    // ROM:
+    // 0x6F05 - LD VF, 5
+    // 0xA050 - LD I, 0x050 (font 0)
+    // 0xDFF5 - DRW VF, VF, 5
+    // 0xDFF5 - DRW VF, VF, 5
+    let mut emulator = Emulator::new(Some(vec![0x6F, 0x05, 0xA0, 0x50, 0xDF, 0xF5, 0xDF, 0xF5]));
+    emulator.quirks.display_wait = false;
+    let recorder = Recorder::default();
+    emulator.add_observer(recorder.clone());
+
+    emulator.step(Keypad::default(), 4);
+    let draws: Vec<_> = recorder
+        .take()
+        .into_iter()
+        .filter(|observed| matches!(observed, Observed::Draw(..)))
+        .collect();
+    // The first draw reports the coordinates before VF is replaced by its
+    // collision flag, which positions the second draw at the origin.
+    assert_eq!(
+        draws,
+        vec![
+            Observed::Draw(5, 5, 5, false),
+            Observed::Draw(0, 0, 5, false)
+        ]
+    );
+}
+
+#[test]
+fn test_observer_sees_sound_and_key_waits() {
+    // ROM:
+    // 0x6002 - LD V0, 2
+    // 0xF018 - LD ST, V0
+    // 0xF30A - LD V3, K
+    let mut emulator = Emulator::new(Some(vec![0x60, 0x02, 0xF0, 0x18, 0xF3, 0x0A]));
+    let recorder = Recorder::default();
+    emulator.add_observer(recorder.clone());
+
+    emulator.step(Keypad::default(), 2);
+    assert!(recorder.take().contains(&Observed::SoundStart));
+
+    // Waiting for a key is only reported once, and the timer runs out meanwhile.
+    for _ in 0..3 {
+        emulator.run_frame(Keypad::default());
+    }
+    let observed = recorder.take();
+    let key_waits = observed
+        .iter()
+        .filter(|observed| matches!(observed, Observed::KeyWait(_)))
+        .count();
+    assert_eq!(key_waits, 1);
+    assert!(observed.contains(&Observed::KeyWait(3)));
+    assert!(observed.contains(&Observed::SoundStop));
+}
@@ -6,3 +6,12 @@ pub fn hydrate() {
 
     leptos::mount::hydrate_body(App);
 }
+
+/// Entry point for `public/chip8-worker.js`.
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn chip8_worker() {
+    _ = console_log::init_with_level(log::Level::Debug);
+    console_error_panic_hook::set_once();
+
+    app::chip8::worker::run_worker();
+}
@@ -0,0 +1,6 @@
+// Loads the site's wasm bundle inside a dedicated worker and hands control to
+// the chip8 emulator (see app/src/chip8/worker.rs).
+import init, { chip8_worker } from "/pkg/brongan_com.js";
+
+await init({ module_or_path: "/pkg/brongan_com.wasm" });
+chip8_worker();
@@ -1,3 +1,4 @@
+use app::mandelbrot;
 use app::state::ServerState;
 use axum::{
     body::Body,
@@ -25,7 +26,8 @@ pub async fn record_analytics(
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    if request.uri().path() == "/_trunk/ws" {
+    // Mandelbrot tiles are fetched on every zoom and pan, not visits.
+    if let "/_trunk/ws" | mandelbrot::remote::ENDPOINT = request.uri().path() {
         return next.run(request).await;
     }
     let path = Path::new(request.uri().path());
@@ -4,6 +4,7 @@ use axum::{
     http::{uri::Scheme, Request, StatusCode, Uri},
     middleware::{self, Next},
     response::Response,
+    routing::get,
     serve, Router,
 };
 use axum_server::tls_rustls::RustlsConfig;
@@ -71,6 +72,10 @@ async fn main() {
     global::set_tracer_provider(tracer_provider);
 
     let app = Router::new()
+        .route(
+            mandelbrot::remote::ENDPOINT,
+            get(mandelbrot::remote::mandelbrot_get),
+        )
         .leptos_routes_with_context(
             &app_state,
             routes,